@@ -3,8 +3,16 @@ use clap::Parser as ClapParser;
 use pest::{iterators::Pair, Parser};
 use pest_derive::Parser as PestParser;
 use rustyline::{error::ReadlineError, DefaultEditor};
+use std::cell::RefCell;
 use tracing::{debug, error, trace, warn};
 
+/// A single put/del buffered between `begin` and `commit`/`abort`.
+#[derive(Debug, Clone)]
+enum BufferedOp {
+    Put(String, String),
+    Del(String),
+}
+
 /// The PEG based parser for Parser CLI.
 ///
 /// # Grammar
@@ -14,20 +22,39 @@ use tracing::{debug, error, trace, warn};
 /// command    = { put_command | get_command | del_command | iter_command }
 ///
 /// put_command  = { put ~ key ~ space ~ value }
-/// get_command  = { get ~ keys }
+/// get_command  = { get ~ keys ~ ( space ~ revision )? }
 /// del_command  = { del ~ keys }
-/// iter_command = { ord ~ ( space ~ key )? }
+/// iter_command = { ord ~ ( space ~ key )? ~ ( space ~ revision )? }
 ///
 /// put = { ( ( ^"put" ~ space ) | "+" ~ ( space )? ) }
 /// get = { ( ( ^"get" ~ space ) | "?" ~ ( space )? ) }
 /// del = { ( ( ^"del" ~ space ) | "-" ~ ( space )? ) }
 /// ord = { ^"asc" | ^"desc" }
 ///
+/// // `@42` pins a `get`/`asc`/`desc` command to a past revision, e.g.
+/// // `? mykey @42` or `asc @42`; omitting it reads the latest revision.
+/// revision = @{ "@" ~ ASCII_DIGIT+ }
+///
 /// space = { SPACE_SEPARATOR+ }
 /// keys  = { key ~ ( space ~ key)* }
-/// key   = @{ ( !space ~ ANY )+ }
-/// value = { ANY+ }
+/// key   = @{ !revision ~ ( !space ~ ANY )+ }
+///
+/// // a value is `null`, `true`/`false`, a number, a quoted string, a `[...]`
+/// // sequence, or a `{key: value, ...}` dictionary, matching the text syntax
+/// // `papyrus::types::value::Value` reads and writes.
+/// value = { null | boolean | float | int | string | seq | dict }
+///
+/// // `range [a b)` scans keys in `[a, b)`; `prefix`/`~` scans by key prefix;
+/// // `begin` buffers put/del commands until `commit`/`abort`, which report
+/// // the buffered count and clear it without writing to a layer.
+/// range_command  = { range ~ lower_bound ~ space ~ upper_bound }
+/// prefix_command = { prefix ~ key }
+/// begin_command  = { ^"begin" }
+/// commit_command = { ^"commit" }
+/// abort_command  = { ^"abort" }
 /// ```
+///
+/// See `papyrus.pest` for the full, authoritative grammar.
 #[derive(PestParser)]
 #[grammar = "papyrus.pest"]
 pub struct PapyrusParser;
@@ -41,6 +68,11 @@ pub struct Papyrus {
 
     #[clap(default_value = "mem://", help = "The URL of the Papyrus location.")]
     url: String,
+
+    /// operations buffered between `begin` and `commit`/`abort`; `None` when
+    /// not inside a transaction.
+    #[clap(skip)]
+    transaction: RefCell<Option<Vec<BufferedOp>>>,
 }
 
 impl Papyrus {
@@ -119,6 +151,12 @@ impl Papyrus {
             }
             Rule::get_command | Rule::del_command => {
                 let operator = pair.as_rule();
+                let revision = pair
+                    .clone()
+                    .into_inner()
+                    .filter(|p| p.as_rule() == Rule::revision)
+                    .next()
+                    .map(|p| p.as_str().to_string());
                 let keys: Vec<String> = pair
                     // seach the keys pair
                     .into_inner()
@@ -132,7 +170,17 @@ impl Papyrus {
                     .map(|p| p.as_str().to_string())
                     .collect();
 
-                println!("{:?} {:?}", operator, keys);
+                if operator == Rule::del_command {
+                    if let Some(ops) = self.transaction.borrow_mut().as_mut() {
+                        ops.extend(keys.into_iter().map(BufferedOp::Del));
+                        return;
+                    }
+                }
+
+                match revision {
+                    Some(revision) => println!("{:?} {:?} at {}", operator, keys, revision),
+                    None => println!("{:?} {:?}", operator, keys),
+                }
             }
             Rule::put_command => {
                 let key_value: Vec<String> = pair
@@ -141,8 +189,76 @@ impl Papyrus {
                     .map(|p| p.as_str().to_string())
                     .collect();
 
+                if let [key, value] = key_value.as_slice() {
+                    if let Some(ops) = self.transaction.borrow_mut().as_mut() {
+                        ops.push(BufferedOp::Put(key.clone(), value.clone()));
+                        return;
+                    }
+                }
+
                 println!("put_command: {:?}", key_value);
             }
+            Rule::range_command => {
+                let mut inner = pair.into_inner();
+                let lower = inner
+                    .find(|p| p.as_rule() == Rule::lower_bound)
+                    .expect("lower_bound pair not found");
+                let upper = inner
+                    .find(|p| p.as_rule() == Rule::upper_bound)
+                    .expect("upper_bound pair not found");
+
+                let lower_inclusive = lower.as_str().starts_with('[');
+                let upper_inclusive = upper.as_str().ends_with(']');
+                let lower_key = lower
+                    .into_inner()
+                    .next()
+                    .expect("bound_key pair not found")
+                    .as_str();
+                let upper_key = upper
+                    .into_inner()
+                    .next()
+                    .expect("bound_key pair not found")
+                    .as_str();
+
+                println!(
+                    "range_command: {}{}, {}{}",
+                    if lower_inclusive { "[" } else { "(" },
+                    lower_key,
+                    upper_key,
+                    if upper_inclusive { "]" } else { ")" },
+                );
+            }
+            Rule::prefix_command => {
+                let key = pair
+                    .into_inner()
+                    .filter(|p| p.as_rule() == Rule::key)
+                    .next()
+                    .expect("key pair not found")
+                    .as_str();
+
+                println!("prefix_command: {:?}", key);
+            }
+            Rule::begin_command => {
+                let mut transaction = self.transaction.borrow_mut();
+
+                if transaction.is_some() {
+                    warn!("already inside a transaction, ignoring nested begin");
+                } else {
+                    *transaction = Some(Vec::new());
+                    println!("begin");
+                }
+            }
+            Rule::commit_command => match self.transaction.borrow_mut().take() {
+                Some(ops) => println!(
+                    "commit: {} buffered operation(s)",
+                    ops.len()
+                ),
+                None => warn!("commit outside of a transaction"),
+            },
+            Rule::abort_command => match self.transaction.borrow_mut().take() {
+                Some(ops) => println!("abort: discarding {} buffered operation(s)", ops.len()),
+                None => warn!("abort outside of a transaction"),
+            },
             Rule::iter_command => {
                 let ord: String = pair
                     .clone()
@@ -157,8 +273,14 @@ impl Papyrus {
                     .filter(|p| p.as_rule() == Rule::key)
                     .map(|p| p.as_str().to_string())
                     .next();
+                let revision: Option<String> = pair
+                    .clone()
+                    .into_inner()
+                    .filter(|p| p.as_rule() == Rule::revision)
+                    .map(|p| p.as_str().to_string())
+                    .next();
 
-                println!("iter_command: {:?} {:?}", ord, key);
+                println!("iter_command: {:?} {:?} {:?}", ord, key, revision);
             }
             _ => {
                 warn!("invalid command: {:?}", pair);
@@ -1,5 +1,6 @@
 //! The command-line tool for Papyrus.
 use clap::Parser;
+use papyrus::{get_layer, Server};
 use rustyline::{error::ReadlineError, DefaultEditor};
 use tracing::{debug, error, trace};
 
@@ -12,6 +13,9 @@ pub struct Papyrus {
 
     #[clap(default_value = "mem://", help = "The URL of the Papyrus location.")]
     url: String,
+
+    #[clap(long, help = "Serve the layer over TCP instead of starting the REPL.")]
+    serve: Option<String>,
 }
 
 impl Papyrus {
@@ -24,11 +28,35 @@ impl Papyrus {
     fn run(&self) -> i32 {
         self.setup_logging();
 
-        self.prologue();
-        let code = self.eval_loop();
-        self.epologue();
+        match &self.serve {
+            Some(addr) => self.serve(addr),
+            None => {
+                self.prologue();
+                let code = self.eval_loop();
+                self.epologue();
 
-        code
+                code
+            }
+        }
+    }
+
+    /// open the configured layer and serve it on the given address, blocking forever.
+    fn serve(&self, addr: &str) -> i32 {
+        let layer = match get_layer(&self.url) {
+            Some(layer) => layer,
+            None => {
+                error!("cannot open layer from {}", self.url);
+                return 1;
+            }
+        };
+
+        match Server::new(layer).serve(addr) {
+            Ok(_) => 0,
+            Err(err) => {
+                error!("failed to serve {}: {:?}", addr, err);
+                1
+            }
+        }
     }
 
     /// the read-eval-print-loop
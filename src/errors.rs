@@ -14,8 +14,28 @@ pub enum Error {
     /// Invalid Argument
     InvalidArgument,
 
+    /// The file is locked by another, still-running process.
+    Locked,
+
+    /// The file's header records an owning PID that is no longer running;
+    /// recoverable by calling `FileBaseLayer::force_unlock` on the path.
+    StaleLock,
+
     /// I/O Error
     IOError(String),
+
+    /// A `Converter`/`Packer` decode failed partway through, at a known
+    /// byte offset into the input: either the buffer ran out before
+    /// `expected` could be read, or it held something other than `expected`
+    /// (e.g. an unknown type tag), recorded in `found`.
+    Decode {
+        /// the byte offset into the input where the failure occurred.
+        offset: usize,
+        /// what the decoder was trying to read at that offset.
+        expected: &'static str,
+        /// a description of what was actually there instead.
+        found: String,
+    },
 }
 
 // ======== value-to-value conversions ========
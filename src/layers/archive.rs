@@ -0,0 +1,338 @@
+//! A portable ustar tar archive format for backing up/restoring a whole
+//! [`Layer`](super::Layer).
+//!
+//! Unlike [`crate::layers::cbor`], which packs a whole layer into a single
+//! CBOR byte string, this module's goal is a *backup*
+//! format: one tar entry per key-value pair, inspectable with any standard
+//! tar tool. A leading [`MANIFEST_NAME`] entry records the file layer's
+//! `type`/`flags` (or `(0, 0)` for layers with no on-disk format, e.g.
+//! [`crate::layers::MemLayer`]) and the archive format version, so an archive
+//! produced by one version of Papyrus can be restored into a freshly
+//! compacted layer of another. A tombstoned key is written with an empty body
+//! under [`TYPEFLAG_DELETED`] rather than folding its tombstone into the
+//! payload, so an archive stays byte-inspectable without decoding `Value`'s
+//! own tag. An entry name longer than ustar's 100-byte field (a hex-encoded
+//! `Key::STR`/`TEXT` past ~47 characters) is carried in a preceding
+//! [`TYPEFLAG_PAX`] extended-header entry instead of being rejected, the same
+//! escape hatch real tar tools use for long names.
+use crate::{Error, Key, Packer, Result, Value};
+use std::io::{Read, Write};
+
+/// the block size every tar header and body padding is measured in.
+const BLOCK_SIZE: usize = 512;
+
+/// the regular-file typeflag used for a live key-value pair (and the manifest).
+const TYPEFLAG_REGULAR: u8 = b'0';
+/// the typeflag marking a tombstoned (deleted) key; its body is always empty.
+const TYPEFLAG_DELETED: u8 = b'D';
+/// the PAX extended-header typeflag: its body is a `path=`-style record
+/// naming the *next* entry, used whenever that name overflows the 100-byte
+/// ustar name field.
+const TYPEFLAG_PAX: u8 = b'x';
+/// the placeholder ustar name written on a [`TYPEFLAG_PAX`] header itself;
+/// readers that understand PAX ignore it and take the real name from the
+/// `path` record, tools that don't at least see a recognizable stand-in.
+const PAX_HEADER_NAME: &str = "PaxHeader";
+
+/// the name of the leading manifest entry, written before any key-value pair.
+const MANIFEST_NAME: &str = "MANIFEST";
+/// the archive format version recorded in the manifest entry.
+const FORMAT_VERSION: u8 = 1;
+
+/// Encode `data` as a lowercase hex string, used to turn a `Key`'s packed
+/// bytes into a tar-safe entry name.
+fn to_hex(data: &[u8]) -> String {
+    data.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Decode a hex string written by [`to_hex`].
+fn from_hex(text: &str) -> Result<Vec<u8>> {
+    if text.len() % 2 != 0 {
+        return Err(Error::InvalidArgument);
+    }
+
+    (0..text.len())
+        .step_by(2)
+        .map(|pos| u8::from_str_radix(&text[pos..pos + 2], 16).map_err(|_| Error::InvalidArgument))
+        .collect()
+}
+
+/// Write a NUL-terminated octal field, left-padded with zeros.
+fn write_octal(field: &mut [u8], value: u64) {
+    let width = field.len() - 1;
+    let text = format!("{:0width$o}", value, width = width);
+
+    field[..width].copy_from_slice(text.as_bytes());
+    field[width] = 0;
+}
+
+/// Parse a NUL/space-terminated octal field.
+fn read_octal(field: &[u8]) -> Result<u64> {
+    let text = std::str::from_utf8(field).map_err(|_| Error::InvalidArgument)?;
+
+    match text.trim_end_matches(['\0', ' ']) {
+        "" => Ok(0),
+        text => u64::from_str_radix(text, 8).map_err(|_| Error::InvalidArgument),
+    }
+}
+
+/// Build the 512-byte ustar header for one entry.
+fn build_header(name: &str, size: usize, typeflag: u8) -> Result<[u8; BLOCK_SIZE]> {
+    if name.len() > 100 {
+        return Err(Error::InvalidArgument);
+    }
+
+    let mut header = [0u8; BLOCK_SIZE];
+    header[..name.len()].copy_from_slice(name.as_bytes());
+    write_octal(&mut header[100..108], 0o644); // mode
+    write_octal(&mut header[124..136], size as u64); // size
+    header[156] = typeflag;
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+    header[148..156].fill(b' '); // checksum field, while the checksum itself is computed
+
+    let checksum: u32 = header.iter().map(|&byte| byte as u32).sum();
+    write_octal(&mut header[148..154], checksum as u64);
+    header[154] = 0;
+    header[155] = b' ';
+
+    Ok(header)
+}
+
+/// Build a PAX extended-header record: a self-describing `"<len> key=value\n"`
+/// line whose `<len>` counts its own digits, per the PAX spec.
+fn pax_record(key: &str, value: &str) -> String {
+    let mut length = key.len() + value.len() + 3; // `' '` + `'='` + `'\n'`
+
+    loop {
+        let candidate = format!("{} {}={}\n", length, key, value);
+        if candidate.len() == length {
+            return candidate;
+        }
+        length = candidate.len();
+    }
+}
+
+/// Extract the `path` record out of a [`TYPEFLAG_PAX`] entry's body.
+fn parse_pax_path(body: &[u8]) -> Result<String> {
+    let mut rest = std::str::from_utf8(body).map_err(|_| Error::InvalidArgument)?;
+
+    while !rest.is_empty() {
+        let space = rest.find(' ').ok_or(Error::InvalidArgument)?;
+        let len: usize = rest[..space].parse().map_err(|_| Error::InvalidArgument)?;
+        let record = rest.get(..len).ok_or(Error::InvalidArgument)?;
+
+        if let Some(value) = record[space + 1..record.len() - 1].strip_prefix("path=") {
+            return Ok(value.to_string());
+        }
+
+        rest = &rest[len..];
+    }
+
+    Err(Error::InvalidArgument)
+}
+
+/// Stream one tar entry: its header, body, and zero padding up to the next
+/// block boundary. A `name` too long for ustar's 100-byte field is preceded
+/// by a [`TYPEFLAG_PAX`] entry carrying the real name, with a truncated
+/// stand-in left in the entry's own header for tools that skip PAX.
+fn write_entry(writer: &mut dyn Write, name: &str, body: &[u8], typeflag: u8) -> Result<()> {
+    if name.len() > 100 {
+        let record = pax_record("path", name).into_bytes();
+        let pax_header = build_header(PAX_HEADER_NAME, record.len(), TYPEFLAG_PAX)?;
+        writer.write_all(&pax_header)?;
+        writer.write_all(&record)?;
+
+        let padding = (BLOCK_SIZE - record.len() % BLOCK_SIZE) % BLOCK_SIZE;
+        writer.write_all(&vec![0u8; padding])?;
+    }
+
+    let header_name = if name.len() > 100 { &name[..100] } else { name };
+    let header = build_header(header_name, body.len(), typeflag)?;
+    writer.write_all(&header)?;
+    writer.write_all(body)?;
+
+    let padding = (BLOCK_SIZE - body.len() % BLOCK_SIZE) % BLOCK_SIZE;
+    writer.write_all(&vec![0u8; padding])?;
+
+    Ok(())
+}
+
+/// Read one raw ustar header, or `None` at the archive's terminating
+/// all-zero block. Does not resolve [`TYPEFLAG_PAX`] entries; use
+/// [`read_entry_header`] for that.
+fn read_header(reader: &mut dyn Read) -> Result<Option<(String, usize, u8)>> {
+    let mut header = [0u8; BLOCK_SIZE];
+    reader.read_exact(&mut header)?;
+
+    if header.iter().all(|&byte| byte == 0) {
+        return Ok(None);
+    }
+
+    let name_len = header[..100]
+        .iter()
+        .position(|&byte| byte == 0)
+        .unwrap_or(100);
+    let name = std::str::from_utf8(&header[..name_len])
+        .map_err(|_| Error::InvalidArgument)?
+        .to_string();
+    let size = read_octal(&header[124..136])? as usize;
+    let typeflag = header[156];
+
+    Ok(Some((name, size, typeflag)))
+}
+
+/// Read the next entry's header like [`read_header`], transparently
+/// resolving a leading [`TYPEFLAG_PAX`] entry into the real name of the
+/// entry that follows it.
+fn read_entry_header(reader: &mut dyn Read) -> Result<Option<(String, usize, u8)>> {
+    let (name, size, typeflag) = match read_header(reader)? {
+        Some(header) => header,
+        None => return Ok(None),
+    };
+
+    if typeflag != TYPEFLAG_PAX {
+        return Ok(Some((name, size, typeflag)));
+    }
+
+    let pax_name = parse_pax_path(&read_body(reader, size)?)?;
+    let (_, real_size, real_typeflag) = read_header(reader)?.ok_or(Error::InvalidArgument)?;
+
+    Ok(Some((pax_name, real_size, real_typeflag)))
+}
+
+/// Read one tar entry's body and its trailing padding, given the size [`read_header`] reported.
+fn read_body(reader: &mut dyn Read, size: usize) -> Result<Vec<u8>> {
+    let mut body = vec![0u8; size];
+    reader.read_exact(&mut body)?;
+
+    let padding = (BLOCK_SIZE - size % BLOCK_SIZE) % BLOCK_SIZE;
+    reader.read_exact(&mut vec![0u8; padding])?;
+
+    Ok(body)
+}
+
+/// Stream a layer's key-value pairs into `writer` as a ustar archive: a
+/// leading [`MANIFEST_NAME`] entry capturing `meta` (the file layer's
+/// `type`/`flags`, or `(0, 0)`) and the format version, followed by one entry
+/// per pair -- its name the hex of the key's packed bytes, its body the
+/// value's packed bytes, or an empty body under [`TYPEFLAG_DELETED`] for a
+/// tombstone.
+pub(crate) fn encode_entries<W, I>(pairs: I, meta: (u8, u16), writer: &mut W) -> Result<()>
+where
+    W: Write,
+    I: Iterator<Item = (Key, Value)>,
+{
+    let (typ, flags) = meta;
+    let manifest = [FORMAT_VERSION, typ, (flags >> 8) as u8, flags as u8];
+    write_entry(writer, MANIFEST_NAME, &manifest, TYPEFLAG_REGULAR)?;
+
+    for (key, value) in pairs {
+        let name = to_hex(&key.pack());
+
+        match value {
+            Value::DELETED => write_entry(writer, &name, &[], TYPEFLAG_DELETED)?,
+            value => write_entry(writer, &name, &value.pack(), TYPEFLAG_REGULAR)?,
+        }
+    }
+
+    writer.write_all(&[0u8; BLOCK_SIZE * 2])?;
+
+    Ok(())
+}
+
+/// Decode a ustar archive written by [`encode_entries`], returning the
+/// manifest's `(type, flags)` and every key-value pair, tombstones restored
+/// as [`Value::DELETED`].
+pub(crate) fn decode_entries<R>(reader: &mut R) -> Result<((u8, u16), Vec<(Key, Value)>)>
+where
+    R: Read,
+{
+    let (name, size, _) = read_entry_header(reader)?.ok_or(Error::InvalidArgument)?;
+    if name != MANIFEST_NAME {
+        return Err(Error::InvalidArgument);
+    }
+
+    let manifest = read_body(reader, size)?;
+    let typ = *manifest.get(1).ok_or(Error::InvalidArgument)?;
+    let flags = u16::from_be_bytes([
+        *manifest.get(2).ok_or(Error::InvalidArgument)?,
+        *manifest.get(3).ok_or(Error::InvalidArgument)?,
+    ]);
+
+    let mut pairs = Vec::new();
+    while let Some((name, size, typeflag)) = read_entry_header(reader)? {
+        let body = read_body(reader, size)?;
+        let (key, _) = Key::unpack(&from_hex(&name)?)?;
+
+        let value = match typeflag {
+            TYPEFLAG_DELETED => Value::DELETED,
+            _ => Value::unpack(&body)?.0,
+        };
+
+        pairs.push((key, value));
+    }
+
+    Ok(((typ, flags), pairs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex_round_trip() {
+        let data = vec![0x00, 0xff, 0x10, 0xab];
+
+        assert_eq!(from_hex(&to_hex(&data)), Ok(data));
+    }
+
+    #[test]
+    fn test_encode_decode_entries_round_trip() {
+        let pairs: Vec<(Key, Value)> = vec![
+            (Key::BOOL(true), Value::DELETED),
+            (Key::INT(-7), "seven".into()),
+            ("key".into(), "value".into()),
+        ];
+
+        let mut archive = Vec::new();
+        encode_entries(pairs.clone().into_iter(), (0x01, 0x1234), &mut archive).unwrap();
+
+        let (meta, decoded) = decode_entries(&mut archive.as_slice()).unwrap();
+
+        assert_eq!(meta, (0x01, 0x1234));
+        assert_eq!(decoded, pairs);
+    }
+
+    #[test]
+    fn test_encode_entries_empty() {
+        let mut archive = Vec::new();
+        encode_entries(std::iter::empty(), (0, 0), &mut archive).unwrap();
+
+        let (meta, decoded) = decode_entries(&mut archive.as_slice()).unwrap();
+
+        assert_eq!(meta, (0, 0));
+        assert_eq!(decoded, vec![]);
+    }
+
+    #[test]
+    fn test_archive_ends_with_two_zero_blocks() {
+        let mut archive = Vec::new();
+        encode_entries(std::iter::empty(), (0, 0), &mut archive).unwrap();
+
+        assert_eq!(&archive[archive.len() - BLOCK_SIZE * 2..], &[0u8; BLOCK_SIZE * 2][..]);
+    }
+
+    #[test]
+    fn test_long_key_name_round_trips_via_pax_header() {
+        let long_key: String = "x".repeat(80);
+        let pairs: Vec<(Key, Value)> = vec![(long_key.as_str().into(), "value".into())];
+
+        let mut archive = Vec::new();
+        encode_entries(pairs.clone().into_iter(), (0, 0), &mut archive).unwrap();
+
+        let (_, decoded) = decode_entries(&mut archive.as_slice()).unwrap();
+
+        assert_eq!(decoded, pairs);
+    }
+}
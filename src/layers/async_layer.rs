@@ -0,0 +1,319 @@
+//! An async counterpart to [`Layer`] for front-ends that want to drive many
+//! concurrent stores without dedicating an OS thread to each one.
+//!
+//! Don't confuse this with [`crate::AsyncClient`]: that trait describes a
+//! *fire-and-forget* request over TCP, while [`AsyncLayer`] describes an
+//! `async fn` API that can be `.await`ed on an executor such as `tokio`.
+//! [`BlockingLayer`] bridges the two worlds by running every [`Layer`] call
+//! on [`tokio::task::spawn_blocking`], which is also what backs the `wal`,
+//! `btree`, `sst` and `mem` schemes behind [`get_async_layer`] -- there is no
+//! dedicated async file format yet, so the existing blocking I/O is simply
+//! moved off the async executor's thread.
+use crate::layers::file::{BTreeLayer, SSTableLayer, WALLayer};
+use crate::layers::MemLayer;
+use crate::{Error, Key, Layer, Result, Value};
+use futures::stream::{self, Stream};
+use std::pin::Pin;
+use tokio::task;
+use tracing::{trace, warn};
+use url::Url;
+
+/// The async counterpart to [`Layer`].
+///
+/// Every method mirrors a [`Layer`] method, `async fn` for `async fn`, with
+/// `forward`/`backward` returning a [`Stream`] instead of a boxed `Iterator`.
+#[async_trait::async_trait]
+pub trait AsyncLayer: Send {
+    /// Open the layer by the passed URL.
+    async fn open(url: &Url) -> Result<Self>
+    where
+        Self: Sized;
+
+    /// Get the value of the specified key, return None if the key does not exist.
+    async fn get(&mut self, key: &Key) -> Option<Value>;
+
+    /// Set the value of the specified key, which may overwrite and return the old value.
+    async fn put(&mut self, key: &Key, value: Value) -> Option<Value>;
+
+    /// Delete the value of the specified key.
+    async fn del(&mut self, key: &Key);
+
+    /// Remove the existing data and files.
+    async fn unlink(&mut self);
+
+    /// Remove all the data marked as deleted, reorganize the data and file.
+    async fn compact(&mut self);
+
+    /// Stream the key-value pairs in ascending order of the key, starting
+    /// at the optional base key (inclusive).
+    async fn forward<'a>(
+        &'a mut self,
+        base: Option<&'a Key>,
+    ) -> Pin<Box<dyn Stream<Item = (Key, Value)> + Send + 'a>>;
+
+    /// Stream the key-value pairs in descending order of the key, starting
+    /// at the optional base key (inclusive).
+    async fn backward<'a>(
+        &'a mut self,
+        base: Option<&'a Key>,
+    ) -> Pin<Box<dyn Stream<Item = (Key, Value)> + Send + 'a>>;
+}
+
+/// Adapts any synchronous [`Layer`] into an [`AsyncLayer`] by running each
+/// call through [`tokio::task::spawn_blocking`].
+///
+/// The inner layer is shuffled into and back out of the blocking task on
+/// every call -- it is briefly absent from `self.inner` while a task is in
+/// flight, which is why [`BlockingLayer`] cannot offer two concurrent calls
+/// against the same instance.
+pub struct BlockingLayer<L: Layer + Send + 'static> {
+    inner: Option<L>,
+}
+
+impl<L: Layer + Send + 'static> BlockingLayer<L> {
+    /// Wrap an already-open synchronous layer.
+    pub fn new(inner: L) -> Self {
+        Self { inner: Some(inner) }
+    }
+
+    /// Run `task` against the inner layer on a blocking thread, then restore it.
+    async fn with_inner<F, T>(&mut self, task: F) -> T
+    where
+        F: FnOnce(&mut L) -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let mut inner = self
+            .inner
+            .take()
+            .expect("BlockingLayer's inner layer is still in use by a prior call");
+
+        let (result, inner) = task::spawn_blocking(move || {
+            let result = task(&mut inner);
+            (result, inner)
+        })
+        .await
+        .expect("blocking layer task panicked");
+
+        self.inner = Some(inner);
+        result
+    }
+}
+
+#[async_trait::async_trait]
+impl<L: Layer + Send + 'static> AsyncLayer for BlockingLayer<L> {
+    async fn open(url: &Url) -> Result<Self> {
+        let url = url.clone();
+        let inner = task::spawn_blocking(move || L::open(&url))
+            .await
+            .map_err(|_| Error::InvalidArgument)??;
+
+        Ok(Self::new(inner))
+    }
+
+    async fn get(&mut self, key: &Key) -> Option<Value> {
+        let key = key.clone();
+        self.with_inner(move |layer| layer.get(&key)).await
+    }
+
+    async fn put(&mut self, key: &Key, value: Value) -> Option<Value> {
+        let key = key.clone();
+        self.with_inner(move |layer| layer.put(&key, value)).await
+    }
+
+    async fn del(&mut self, key: &Key) {
+        let key = key.clone();
+        self.with_inner(move |layer| layer.del(&key)).await
+    }
+
+    async fn unlink(&mut self) {
+        self.with_inner(|layer| layer.unlink()).await
+    }
+
+    async fn compact(&mut self) {
+        self.with_inner(|layer| layer.compact()).await
+    }
+
+    async fn forward<'a>(
+        &'a mut self,
+        base: Option<&'a Key>,
+    ) -> Pin<Box<dyn Stream<Item = (Key, Value)> + Send + 'a>> {
+        let base = base.cloned();
+        let pairs: Vec<(Key, Value)> = self
+            .with_inner(move |layer| layer.forward(base.as_ref()).collect())
+            .await;
+
+        Box::pin(stream::iter(pairs))
+    }
+
+    async fn backward<'a>(
+        &'a mut self,
+        base: Option<&'a Key>,
+    ) -> Pin<Box<dyn Stream<Item = (Key, Value)> + Send + 'a>> {
+        let base = base.cloned();
+        let pairs: Vec<(Key, Value)> = self
+            .with_inner(move |layer| layer.backward(base.as_ref()).collect())
+            .await;
+
+        Box::pin(stream::iter(pairs))
+    }
+}
+
+/// Get the async Layer via passed URL, parallel to [`crate::get_layer`].
+pub fn get_async_layer(url: &str) -> Option<Box<dyn AsyncLayer>> {
+    trace!("try to get async layer from {}", url);
+
+    match Url::parse(url) {
+        Ok(url) => match url.scheme() {
+            "mem" => match MemLayer::open(&url) {
+                Ok(layer) => Some(Box::new(BlockingLayer::new(layer))),
+                Err(err) => {
+                    trace!("failed to open {}: {:?}", &url, err);
+                    None
+                }
+            },
+            "wal" => match WALLayer::open(&url) {
+                Ok(layer) => Some(Box::new(BlockingLayer::new(layer))),
+                Err(err) => {
+                    trace!("failed to open {}: {:?}", &url, err);
+                    None
+                }
+            },
+            "btree" => match BTreeLayer::open(&url) {
+                Ok(layer) => Some(Box::new(BlockingLayer::new(layer))),
+                Err(err) => {
+                    trace!("failed to open {}: {:?}", &url, err);
+                    None
+                }
+            },
+            "sst" => match SSTableLayer::open(&url) {
+                Ok(layer) => Some(Box::new(BlockingLayer::new(layer))),
+                Err(err) => {
+                    trace!("failed to open {}: {:?}", &url, err);
+                    None
+                }
+            },
+            _ => {
+                warn!("cannot find scheme {} for async layer", url.scheme());
+                None
+            }
+        },
+        Err(err) => {
+            trace!("failed to parse url {}: {}", url, err);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+    use paste::paste;
+
+    /// Removes the file backing a `wal://`/`btree://`/`sst://` test layer on
+    /// drop; a no-op for `mem://`, which never creates one.
+    struct TestContext {
+        path: String,
+    }
+
+    impl TestContext {
+        fn new(path: &str) -> Self {
+            Self {
+                path: path.to_string(),
+            }
+        }
+    }
+
+    impl Drop for TestContext {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+
+    /// Exercises [`get_async_layer`] against every file-backed scheme it
+    /// supports as well as `mem://`, since [`BlockingLayer`] was built to
+    /// carry `wal`, `btree` and `sst` onto `spawn_blocking`, not just the
+    /// in-memory layer.
+    macro_rules! test_async_layer {
+        ($scheme:ident) => {
+            paste! {
+                #[tokio::test]
+                async fn [<test_get_async_layer_from_ $scheme>]() {
+                    let path = stringify!([<test_get_async_layer_from_ $scheme>]);
+                    let _ctx = TestContext::new(path);
+                    let url = format!("{}://{}", stringify!($scheme), path);
+                    let layer = get_async_layer(&url);
+
+                    assert_eq!(layer.is_some(), true);
+                }
+
+                #[tokio::test]
+                async fn [<test_async_layer_put_and_get_on_ $scheme>]() {
+                    let path = stringify!([<test_async_layer_put_and_get_on_ $scheme>]);
+                    let _ctx = TestContext::new(path);
+                    let url = format!("{}://{}", stringify!($scheme), path);
+                    let key: Key = "key".into();
+                    let value: Value = "value".into();
+                    let mut layer = get_async_layer(&url).unwrap();
+
+                    layer.put(&key, value.clone()).await;
+
+                    assert_eq!(layer.get(&key).await, Some(value));
+                }
+
+                #[tokio::test]
+                async fn [<test_async_layer_put_and_del_on_ $scheme>]() {
+                    let path = stringify!([<test_async_layer_put_and_del_on_ $scheme>]);
+                    let _ctx = TestContext::new(path);
+                    let url = format!("{}://{}", stringify!($scheme), path);
+                    let key: Key = "key".into();
+                    let value: Value = "value".into();
+                    let mut layer = get_async_layer(&url).unwrap();
+
+                    layer.put(&key, value.clone()).await;
+                    layer.del(&key).await;
+
+                    assert_eq!(layer.get(&key).await, Some(Value::DELETED));
+                }
+
+                #[tokio::test]
+                async fn [<test_async_layer_forward_on_ $scheme>]() {
+                    let path = stringify!([<test_async_layer_forward_on_ $scheme>]);
+                    let _ctx = TestContext::new(path);
+                    let url = format!("{}://{}", stringify!($scheme), path);
+                    let mut layer = get_async_layer(&url).unwrap();
+
+                    for index in 0..4 {
+                        let key: Key = index.into();
+                        let value: Value = format!("value {}", index).into();
+
+                        layer.put(&key, value).await;
+                    }
+
+                    let pairs: Vec<(Key, Value)> = layer.forward(None).await.collect().await;
+                    assert_eq!(pairs.len(), 4);
+                }
+
+                #[tokio::test]
+                async fn [<test_async_layer_unlink_on_ $scheme>]() {
+                    let path = stringify!([<test_async_layer_unlink_on_ $scheme>]);
+                    let _ctx = TestContext::new(path);
+                    let url = format!("{}://{}", stringify!($scheme), path);
+                    let key: Key = "key".into();
+                    let value: Value = "value".into();
+                    let mut layer = get_async_layer(&url).unwrap();
+
+                    layer.put(&key, value).await;
+                    layer.unlink().await;
+
+                    assert_eq!(layer.get(&key).await, None);
+                }
+            }
+        };
+    }
+
+    test_async_layer!(mem);
+    test_async_layer!(wal);
+    test_async_layer!(btree);
+    test_async_layer!(sst);
+}
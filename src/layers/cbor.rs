@@ -0,0 +1,430 @@
+//! Standard CBOR (RFC 8949) encoding for exporting/importing a whole [`Layer`](super::Layer).
+//!
+//! Unlike [`crate::CompactPacker`], which reuses CBOR's header shape for its
+//! own internal type tags, this module maps `Key`/`Value` onto CBOR's actual
+//! major types so the result can be read by any standard CBOR tool: a
+//! top-level array of 2-element `[key, value]` arrays, integers as CBOR ints,
+//! `STR`/`TEXT` as CBOR text strings, `UID` as a CBOR byte string of its 16
+//! raw bytes, the `Value::DELETED` tombstone as CBOR `null`, `Value::BYTES32`
+//! as a CBOR byte string wrapped in [`TAG_BYTES32`] so it round-trips back
+//! to `BYTES32` rather than the plain `RAW` a same-length byte string would
+//! otherwise decode to, and `Value`'s compound `SEQ`/`DICT` variants as CBOR
+//! arrays/maps.
+use crate::{Error, Key, Result, Value};
+
+const MAJOR_UINT: u8 = 0;
+const MAJOR_NEGINT: u8 = 1;
+const MAJOR_BYTES: u8 = 2;
+const MAJOR_TEXT: u8 = 3;
+const MAJOR_ARRAY: u8 = 4;
+const MAJOR_MAP: u8 = 5;
+const MAJOR_TAG: u8 = 6;
+const MAJOR_SIMPLE: u8 = 7;
+
+/// an unregistered (private-use) CBOR tag wrapping a 32-byte string item,
+/// used to keep [`Value::BYTES32`] distinguishable from [`Value::RAW`] on
+/// decode: both are otherwise plain CBOR byte strings of the same shape. A
+/// generic CBOR reader that doesn't know this tag still decodes the byte
+/// string underneath it correctly, per RFC 8949 section 3.4 -- it just
+/// can't tell it apart from a same-length `RAW`.
+const TAG_BYTES32: u64 = 32769;
+
+/// additional-info 27 under [`MAJOR_SIMPLE`] is CBOR's double-precision float:
+/// 8 big-endian bytes carrying the IEEE 754 bit pattern follow the header.
+const SIMPLE_FLOAT_HEADER_LEN: usize = 9;
+
+const SIMPLE_FALSE: u64 = 20;
+const SIMPLE_TRUE: u64 = 21;
+const SIMPLE_NULL: u64 = 22;
+
+/// Encode a `(major_type, value)` pair into a CBOR header, following the same
+/// "short count, then 1/2/4/8 big-endian bytes" layout as every other CBOR item.
+fn encode_header(major: u8, value: u64) -> Vec<u8> {
+    match value {
+        0..=23 => vec![(major << 5) | (value as u8)],
+        24..=0xFF => vec![(major << 5) | 24, value as u8],
+        0x100..=0xFFFF => {
+            let mut data = vec![(major << 5) | 25];
+            data.extend_from_slice(&(value as u16).to_be_bytes());
+            data
+        }
+        0x1_0000..=0xFFFF_FFFF => {
+            let mut data = vec![(major << 5) | 26];
+            data.extend_from_slice(&(value as u32).to_be_bytes());
+            data
+        }
+        _ => {
+            let mut data = vec![(major << 5) | 27];
+            data.extend_from_slice(&value.to_be_bytes());
+            data
+        }
+    }
+}
+
+/// Decode a CBOR header, returning `(major_type, value, header_len)`.
+fn decode_header(data: &[u8]) -> Result<(u8, u64, usize)> {
+    let head = *data.first().ok_or(Error::InvalidArgument)?;
+    let major = head >> 5;
+    let info = head & 0x1F;
+
+    match info {
+        0..=23 => Ok((major, info as u64, 1)),
+        24 => {
+            let byte = *data.get(1).ok_or(Error::InvalidArgument)?;
+            Ok((major, byte as u64, 2))
+        }
+        25 => {
+            let buf: [u8; 2] = data
+                .get(1..3)
+                .ok_or(Error::InvalidArgument)?
+                .try_into()
+                .unwrap();
+            Ok((major, u16::from_be_bytes(buf) as u64, 3))
+        }
+        26 => {
+            let buf: [u8; 4] = data
+                .get(1..5)
+                .ok_or(Error::InvalidArgument)?
+                .try_into()
+                .unwrap();
+            Ok((major, u32::from_be_bytes(buf) as u64, 5))
+        }
+        27 => {
+            let buf: [u8; 8] = data
+                .get(1..9)
+                .ok_or(Error::InvalidArgument)?
+                .try_into()
+                .unwrap();
+            Ok((major, u64::from_be_bytes(buf), 9))
+        }
+        _ => Err(Error::InvalidArgument),
+    }
+}
+
+/// Encode a `Key` as the CBOR type it naturally maps to.
+pub(crate) fn encode_key(key: &Key) -> Vec<u8> {
+    match key {
+        Key::BOOL(value) => {
+            encode_header(MAJOR_SIMPLE, if *value { SIMPLE_TRUE } else { SIMPLE_FALSE })
+        }
+        Key::INT(value) if *value >= 0 => encode_header(MAJOR_UINT, *value as u64),
+        Key::INT(value) => encode_header(MAJOR_NEGINT, (-1 - *value) as u64),
+        Key::UID(value) => {
+            let raw = value.to_be_bytes();
+            let mut data = encode_header(MAJOR_BYTES, raw.len() as u64);
+            data.extend_from_slice(&raw);
+            data
+        }
+        Key::STR(value) | Key::TEXT(value) => {
+            let raw = value.as_bytes();
+            let mut data = encode_header(MAJOR_TEXT, raw.len() as u64);
+            data.extend_from_slice(raw);
+            data
+        }
+    }
+}
+
+/// Decode a `Key` from the CBOR form written by [`encode_key`].
+pub(crate) fn decode_key(data: &[u8]) -> Result<(Key, &[u8])> {
+    let (major, value, header_len) = decode_header(data)?;
+    let rest = &data[header_len..];
+
+    match major {
+        MAJOR_SIMPLE if value == SIMPLE_TRUE => Ok((Key::BOOL(true), rest)),
+        MAJOR_SIMPLE if value == SIMPLE_FALSE => Ok((Key::BOOL(false), rest)),
+        MAJOR_UINT => Ok((Key::INT(value as i64), rest)),
+        MAJOR_NEGINT => Ok((Key::INT(-1 - value as i64), rest)),
+        MAJOR_BYTES => {
+            let size = value as usize;
+            let raw = rest.get(..size).ok_or(Error::InvalidArgument)?;
+            let uid = u128::from_be_bytes(raw.try_into().map_err(|_| Error::InvalidArgument)?);
+
+            Ok((Key::UID(uid), &rest[size..]))
+        }
+        MAJOR_TEXT => {
+            let size = value as usize;
+            let raw = rest.get(..size).ok_or(Error::InvalidArgument)?;
+            let text = String::from_utf8(raw.to_vec()).map_err(|_| Error::InvalidArgument)?;
+
+            Ok((text.as_str().into(), &rest[size..]))
+        }
+        _ => Err(Error::InvalidArgument),
+    }
+}
+
+/// Encode an `f64`'s bit pattern as CBOR's double-precision float item: major
+/// 7, additional info 27, unconditionally (unlike [`encode_header`], which
+/// would shrink a small bit pattern into a shorter header and corrupt it).
+fn encode_float(value: f64) -> Vec<u8> {
+    let mut data = vec![(MAJOR_SIMPLE << 5) | 27];
+    data.extend_from_slice(&value.to_bits().to_be_bytes());
+    data
+}
+
+/// Encode a `Value` as the CBOR type it naturally maps to.
+pub(crate) fn encode_value(value: &Value) -> Vec<u8> {
+    match value {
+        Value::DELETED => encode_header(MAJOR_SIMPLE, SIMPLE_NULL),
+        Value::EMPTY => encode_header(MAJOR_BYTES, 0),
+        Value::BOOL(value) => {
+            encode_header(MAJOR_SIMPLE, if *value { SIMPLE_TRUE } else { SIMPLE_FALSE })
+        }
+        Value::UINT(value) => encode_header(MAJOR_UINT, *value),
+        Value::INT(value) if *value >= 0 => encode_header(MAJOR_UINT, *value as u64),
+        Value::INT(value) => encode_header(MAJOR_NEGINT, (-1 - *value) as u64),
+        Value::FLOAT(value) => encode_float(*value),
+        Value::BYTES32(bytes) => {
+            let mut data = encode_header(MAJOR_TAG, TAG_BYTES32);
+            data.extend(encode_header(MAJOR_BYTES, bytes.len() as u64));
+            data.extend_from_slice(bytes);
+            data
+        }
+        Value::RAW(raw) => {
+            let mut data = encode_header(MAJOR_BYTES, raw.len() as u64);
+            data.extend_from_slice(raw);
+            data
+        }
+        Value::STR(text) => {
+            let raw = text.as_bytes();
+            let mut data = encode_header(MAJOR_TEXT, raw.len() as u64);
+            data.extend_from_slice(raw);
+            data
+        }
+        Value::SEQ(items) => {
+            let mut data = encode_header(MAJOR_ARRAY, items.len() as u64);
+            for item in items {
+                data.extend(encode_value(item));
+            }
+            data
+        }
+        Value::DICT(map) => {
+            let mut data = encode_header(MAJOR_MAP, map.len() as u64);
+            for (key, value) in map {
+                data.extend(encode_value(&Value::STR(key.clone())));
+                data.extend(encode_value(value));
+            }
+            data
+        }
+    }
+}
+
+/// Decode a `Value` from the CBOR form written by [`encode_value`].
+pub(crate) fn decode_value(data: &[u8]) -> Result<(Value, &[u8])> {
+    let (major, value, header_len) = decode_header(data)?;
+    let rest = &data[header_len..];
+
+    match major {
+        MAJOR_SIMPLE if value == SIMPLE_NULL => Ok((Value::DELETED, rest)),
+        MAJOR_SIMPLE if value == SIMPLE_TRUE => Ok((Value::BOOL(true), rest)),
+        MAJOR_SIMPLE if value == SIMPLE_FALSE => Ok((Value::BOOL(false), rest)),
+        MAJOR_SIMPLE if header_len == SIMPLE_FLOAT_HEADER_LEN => {
+            Ok((Value::FLOAT(f64::from_bits(value)), rest))
+        }
+        MAJOR_UINT => Ok((Value::UINT(value), rest)),
+        MAJOR_NEGINT => Ok((Value::INT(-1 - value as i64), rest)),
+        MAJOR_BYTES => {
+            let size = value as usize;
+            let raw = rest.get(..size).ok_or(Error::InvalidArgument)?;
+            let value = match size {
+                0 => Value::EMPTY,
+                _ => Value::RAW(raw.to_vec()),
+            };
+
+            Ok((value, &rest[size..]))
+        }
+        MAJOR_TEXT => {
+            let size = value as usize;
+            let raw = rest.get(..size).ok_or(Error::InvalidArgument)?;
+            let text = String::from_utf8(raw.to_vec()).map_err(|_| Error::InvalidArgument)?;
+
+            Ok((Value::STR(text), &rest[size..]))
+        }
+        MAJOR_TAG if value == TAG_BYTES32 => {
+            let (inner_major, inner_len, inner_header_len) = decode_header(rest)?;
+            if inner_major != MAJOR_BYTES || inner_len != 32 {
+                return Err(Error::InvalidArgument);
+            }
+
+            let raw = rest
+                .get(inner_header_len..inner_header_len + 32)
+                .ok_or(Error::InvalidArgument)?;
+            let bytes: [u8; 32] = raw.try_into().map_err(|_| Error::InvalidArgument)?;
+
+            Ok((Value::BYTES32(bytes), &rest[inner_header_len + 32..]))
+        }
+        MAJOR_ARRAY => {
+            let mut rest = rest;
+            let mut items = Vec::with_capacity(value as usize);
+
+            for _ in 0..value {
+                let item;
+                (item, rest) = decode_value(rest)?;
+                items.push(item);
+            }
+
+            Ok((Value::SEQ(items), rest))
+        }
+        MAJOR_MAP => {
+            let mut rest = rest;
+            let mut map = std::collections::BTreeMap::new();
+
+            for _ in 0..value {
+                let key;
+                let value;
+                (key, rest) = decode_value(rest)?;
+                (value, rest) = decode_value(rest)?;
+
+                let key = match key {
+                    Value::STR(key) => key,
+                    _ => return Err(Error::InvalidArgument),
+                };
+
+                map.insert(key, value);
+            }
+
+            Ok((Value::DICT(map), rest))
+        }
+        _ => Err(Error::InvalidArgument),
+    }
+}
+
+/// Encode a whole layer's contents as a top-level CBOR array of `[key, value]` arrays.
+pub(crate) fn encode_pairs<I>(pairs: I) -> Vec<u8>
+where
+    I: ExactSizeIterator<Item = (Key, Value)>,
+{
+    let mut data = encode_header(MAJOR_ARRAY, pairs.len() as u64);
+
+    for (key, value) in pairs {
+        data.extend(encode_header(MAJOR_ARRAY, 2));
+        data.extend(encode_key(&key));
+        data.extend(encode_value(&value));
+    }
+
+    data
+}
+
+/// Decode the top-level CBOR array written by [`encode_pairs`].
+pub(crate) fn decode_pairs(data: &[u8]) -> Result<Vec<(Key, Value)>> {
+    let (major, count, header_len) = decode_header(data)?;
+    if major != MAJOR_ARRAY {
+        return Err(Error::InvalidArgument);
+    }
+
+    let mut rest = &data[header_len..];
+    let mut pairs = Vec::with_capacity(count as usize);
+
+    for _ in 0..count {
+        let (entry_major, entry_len, entry_header_len) = decode_header(rest)?;
+        if entry_major != MAJOR_ARRAY || entry_len != 2 {
+            return Err(Error::InvalidArgument);
+        }
+        rest = &rest[entry_header_len..];
+
+        let key;
+        let value;
+        (key, rest) = decode_key(rest)?;
+        (value, rest) = decode_value(rest)?;
+
+        pairs.push((key, value));
+    }
+
+    Ok(pairs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    macro_rules! test_key_cbor {
+        ($name:ident, $key:expr) => {
+            paste::paste! {
+                #[test]
+                fn [<test_key_cbor_ $name>]() {
+                    let key: Key = $key;
+                    let data = encode_key(&key);
+                    let (decoded, rest) = decode_key(&data).unwrap();
+
+                    assert_eq!(decoded, key);
+                    assert_eq!(rest.len(), 0);
+                }
+            }
+        };
+    }
+
+    test_key_cbor!(bool_true, Key::BOOL(true));
+    test_key_cbor!(bool_false, Key::BOOL(false));
+    test_key_cbor!(positive_int, Key::INT(42));
+    test_key_cbor!(negative_int, Key::INT(-42));
+    test_key_cbor!(uid, Key::UID(0x1234));
+    test_key_cbor!(str, "key".into());
+    test_key_cbor!(text, "t".repeat(100).as_str().into());
+
+    macro_rules! test_value_cbor {
+        ($name:ident, $value:expr) => {
+            paste::paste! {
+                #[test]
+                fn [<test_value_cbor_ $name>]() {
+                    let value: Value = $value;
+                    let data = encode_value(&value);
+                    let (decoded, rest) = decode_value(&data).unwrap();
+
+                    assert_eq!(decoded, value);
+                    assert_eq!(rest.len(), 0);
+                }
+            }
+        };
+    }
+
+    test_value_cbor!(empty, Value::EMPTY);
+    test_value_cbor!(deleted, Value::DELETED);
+    test_value_cbor!(raw, "value".into());
+    test_value_cbor!(bool_true, Value::BOOL(true));
+    test_value_cbor!(bool_false, Value::BOOL(false));
+    test_value_cbor!(uint, Value::UINT(42));
+    test_value_cbor!(negative_int, Value::INT(-42));
+    test_value_cbor!(float, Value::FLOAT(3.5));
+    test_value_cbor!(str, Value::STR("hello".to_string()));
+    test_value_cbor!(bytes32, Value::BYTES32([7u8; 32]));
+    test_value_cbor!(
+        seq,
+        Value::SEQ(vec![Value::UINT(1), Value::STR("two".to_string())])
+    );
+    test_value_cbor!(dict, {
+        let mut map = std::collections::BTreeMap::new();
+        map.insert("a".to_string(), Value::BOOL(true));
+        Value::DICT(map)
+    });
+
+    #[test]
+    fn test_value_cbor_32_byte_raw_is_not_confused_with_bytes32() {
+        let value: Value = Value::RAW(vec![7u8; 32]);
+        let data = encode_value(&value);
+        let (decoded, rest) = decode_value(&data).unwrap();
+
+        assert_eq!(decoded, value);
+        assert_eq!(rest.len(), 0);
+    }
+
+    #[test]
+    fn test_encode_decode_pairs_round_trip() {
+        let pairs: Vec<(Key, Value)> = vec![
+            (Key::BOOL(true), Value::DELETED),
+            (Key::INT(-7), "seven".into()),
+            ("key".into(), Value::EMPTY),
+        ];
+
+        let data = encode_pairs(pairs.clone().into_iter());
+        let decoded = decode_pairs(&data).unwrap();
+
+        assert_eq!(decoded, pairs);
+    }
+
+    #[test]
+    fn test_encode_pairs_empty() {
+        let data = encode_pairs(std::iter::empty());
+        let decoded = decode_pairs(&data).unwrap();
+
+        assert_eq!(decoded, vec![]);
+    }
+}
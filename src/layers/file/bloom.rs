@@ -0,0 +1,186 @@
+//! A simple Bloom filter, used by [`super::sstable::SSTableLayer`] to skip
+//! blocks that cannot possibly contain a key without touching disk.
+use crate::Key;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// A fixed-size, append-only Bloom filter over [`Key`]s.
+///
+/// Membership checks never false-negative: once a key is inserted,
+/// [`BloomFilter::might_contain`] always returns `true` for it afterwards. It
+/// may false-positive for keys that were never inserted, at a rate governed
+/// by the `bits_per_key`/`hash_count` a filter was constructed with -- either
+/// the tuned defaults in [`Self::new`], or the pair [`Self::with_fpr`]
+/// derives for a caller-chosen target rate. `hash_count` travels with the
+/// serialized filter so a table read back later probes it exactly as it was
+/// built, even if the target rate has since changed.
+#[derive(Debug, Clone)]
+pub(crate) struct BloomFilter {
+    bits: Vec<u8>,
+    hash_count: u32,
+}
+
+impl BloomFilter {
+    /// bits of filter state kept per expected key, tuned for roughly a 1%
+    /// false positive rate at [`Self::HASH_COUNT`] hash probes.
+    const BITS_PER_KEY: usize = 10;
+    /// number of independent hash probes per key.
+    const HASH_COUNT: u32 = 7;
+
+    /// Create an empty filter sized for `expected_keys` entries, tuned for
+    /// roughly a 1% false positive rate.
+    pub(crate) fn new(expected_keys: usize) -> Self {
+        Self::sized(expected_keys, Self::BITS_PER_KEY, Self::HASH_COUNT)
+    }
+
+    /// Create an empty filter sized for `expected_keys` entries, deriving
+    /// `bits_per_key`/`hash_count` from a target false-positive rate via the
+    /// standard `bits_per_key = -log2(fpr) / ln(2)`, `hash_count = bits_per_key * ln(2)`
+    /// formulas. `fpr` is clamped to `[1e-6, 0.5]` so a bogus setting cannot
+    /// zero out or invert the filter.
+    pub(crate) fn with_fpr(expected_keys: usize, fpr: f64) -> Self {
+        let fpr = fpr.clamp(1e-6, 0.5);
+        let bits_per_key = (-fpr.log2() / std::f64::consts::LN_2).ceil().max(1.0) as usize;
+        let hash_count = ((bits_per_key as f64) * std::f64::consts::LN_2).round().max(1.0) as u32;
+
+        Self::sized(expected_keys, bits_per_key, hash_count)
+    }
+
+    /// Shared constructor behind [`Self::new`]/[`Self::with_fpr`].
+    fn sized(expected_keys: usize, bits_per_key: usize, hash_count: u32) -> Self {
+        let bits = (expected_keys.max(1) * bits_per_key + 7) / 8;
+
+        Self {
+            bits: vec![0u8; bits],
+            hash_count,
+        }
+    }
+
+    /// Record that `key` is present in the set.
+    pub(crate) fn insert(&mut self, key: &Key) {
+        let (h1, h2) = Self::hashes(key);
+
+        for i in 0..self.hash_count {
+            let bit = Self::bit_index(h1, h2, i, self.bits.len());
+            self.bits[bit / 8] |= 1 << (bit % 8);
+        }
+    }
+
+    /// Check whether `key` may be present. `false` means definitely absent.
+    pub(crate) fn might_contain(&self, key: &Key) -> bool {
+        if self.bits.is_empty() {
+            return false;
+        }
+
+        let (h1, h2) = Self::hashes(key);
+
+        (0..self.hash_count).all(|i| {
+            let bit = Self::bit_index(h1, h2, i, self.bits.len());
+            self.bits[bit / 8] & (1 << (bit % 8)) != 0
+        })
+    }
+
+    /// Serialize the filter's `hash_count` and bit array.
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        let mut data = self.hash_count.to_be_bytes().to_vec();
+        data.extend_from_slice(&self.bits);
+
+        data
+    }
+
+    /// Restore a filter from the bytes produced by [`Self::to_bytes`].
+    pub(crate) fn from_bytes(data: &[u8]) -> Self {
+        if data.len() < 4 {
+            return Self::new(0);
+        }
+
+        let (head, bits) = data.split_at(4);
+
+        Self {
+            bits: bits.to_vec(),
+            hash_count: u32::from_be_bytes(head.try_into().unwrap()),
+        }
+    }
+
+    /// Derive the `i`-th probe's bit offset via Kirsch-Mitzenmacher double hashing.
+    fn bit_index(h1: u64, h2: u64, i: u32, len_bytes: usize) -> usize {
+        let combined = h1.wrapping_add((i as u64).wrapping_mul(h2));
+
+        (combined % (len_bytes as u64 * 8)) as usize
+    }
+
+    /// Derive two independent hashes for `key`.
+    fn hashes(key: &Key) -> (u64, u64) {
+        let mut first = DefaultHasher::new();
+        key.hash(&mut first);
+
+        let mut second = DefaultHasher::new();
+        0xA5A5_A5A5_A5A5_A5A5u64.hash(&mut second);
+        key.hash(&mut second);
+
+        (first.finish(), second.finish())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bloom_filter_contains_inserted_key() {
+        let mut filter = BloomFilter::new(8);
+        let key: Key = "key".into();
+
+        filter.insert(&key);
+        assert_eq!(filter.might_contain(&key), true);
+    }
+
+    #[test]
+    fn test_bloom_filter_empty_filter_rejects_everything() {
+        let filter = BloomFilter::new(8);
+        let key: Key = "key".into();
+
+        assert_eq!(filter.might_contain(&key), false);
+    }
+
+    #[test]
+    fn test_bloom_filter_round_trips_through_bytes() {
+        let mut filter = BloomFilter::new(8);
+        let key: Key = "key".into();
+        filter.insert(&key);
+
+        let data = filter.to_bytes();
+        let restored = BloomFilter::from_bytes(&data);
+
+        assert_eq!(restored.might_contain(&key), true);
+    }
+
+    #[test]
+    fn test_bloom_filter_with_fpr_contains_inserted_key() {
+        let mut filter = BloomFilter::with_fpr(8, 0.001);
+        let key: Key = "key".into();
+
+        filter.insert(&key);
+        assert_eq!(filter.might_contain(&key), true);
+    }
+
+    #[test]
+    fn test_bloom_filter_with_fpr_round_trips_hash_count() {
+        let mut filter = BloomFilter::with_fpr(8, 0.001);
+        let key: Key = "key".into();
+        filter.insert(&key);
+
+        let restored = BloomFilter::from_bytes(&filter.to_bytes());
+
+        assert_eq!(restored.hash_count, filter.hash_count);
+        assert_eq!(restored.might_contain(&key), true);
+    }
+
+    #[test]
+    fn test_bloom_filter_lower_fpr_uses_more_hashes() {
+        let loose = BloomFilter::with_fpr(8, 0.1);
+        let tight = BloomFilter::with_fpr(8, 0.0001);
+
+        assert!(tight.hash_count > loose.hash_count);
+    }
+}
@@ -0,0 +1,896 @@
+//! The persistent B+tree-like storage Layer.
+use super::file::{FileBaseLayer, FileLayer};
+use crate::{Error, Key, Layer, Packer, Result, Value};
+use crc::{Crc, CRC_32_CKSUM};
+use tracing::error;
+use url::Url;
+
+/// the fixed size of every node page, matching the data the request asked
+/// for: "a fixed-size page (e.g. 4 KiB)".
+const PAGE_SIZE: usize = 4096;
+
+/// the number of keys a page may hold before [`BTreeLayer::split_node`] is
+/// triggered, chosen to comfortably fit [`PAGE_SIZE`] for typical keys.
+const MAX_ENTRIES: usize = 64;
+
+/// one leaf slot: a key and the `(value_offset, value_len)` tuple pointing
+/// into the append-only value arena.
+#[derive(Debug, Clone)]
+struct LeafEntry {
+    key: Key,
+    value_offset: u64,
+    value_len: u32,
+}
+
+/// a decoded B-tree page: a leaf holding `(key, value)` slots, or an internal
+/// node routing to `keys.len() + 1` children.
+#[derive(Debug, Clone)]
+enum Node {
+    Leaf(Vec<LeafEntry>),
+    Internal(Vec<Key>, Vec<u64>),
+}
+
+/// The persistent B+tree-like storage Layer.
+///
+/// It lays the data section out as three regions recorded in an 8-byte
+/// super-block written right after the 16-byte header: a root page offset,
+/// a key/node arena of fixed-size [`PAGE_SIZE`] pages, and an append-only
+/// value arena. Every page is `leaf` or `internal`; a leaf holds sorted
+/// `Key` byte-slices alongside `(value_offset, value_len)` tuples into the
+/// value arena, an internal page holds sorted keys and child page offsets.
+/// Each page carries a trailing CRC so page corruption, not just header
+/// corruption, is detected on read.
+///
+/// `put` appends the encoded [`Value`] to the value arena, then walks from
+/// the root splitting any full page on the way down before descending into
+/// it, so the tree never needs to split on the way back up. The median key
+/// of a split internal page is promoted and removed; the median key of a
+/// split leaf is copied up as a separator, B+tree-style, since leaves are
+/// the only pages holding values.
+///
+/// `put` overwrites a leaf slot in place rather than retaining prior
+/// versions, so this layer keeps no history to serve: [`Layer::get_at`]/
+/// [`Layer::history`] are left on the trait's latest-value-only default.
+/// Only [`super::super::mem::MemLayer`] currently offers real revision
+/// history.
+pub struct BTreeLayer {
+    /// the basic file layer and handle the file operations.
+    base: FileBaseLayer,
+    /// the path used to re-open a fresh file on [`Layer::compact`].
+    path: String,
+}
+
+impl FileLayer for BTreeLayer {
+    /// The type of the BTree layer.
+    const TYPE: u8 = 0x02;
+}
+
+impl BTreeLayer {
+    /// Read the super-block, seeding it with an empty root (`0`) on a
+    /// brand new file.
+    fn load_root(&mut self) -> Result<u64> {
+        let mut buf = [0u8; 8];
+
+        match self.base.read_at(&mut buf, 0) {
+            Ok(()) => Ok(u64::from_be_bytes(buf)),
+            Err(_) => {
+                self.base.append(&0u64.to_be_bytes())?;
+                Ok(0)
+            }
+        }
+    }
+
+    /// Overwrite the super-block's root page offset.
+    fn set_root(&mut self, offset: u64) -> Result<()> {
+        self.base.write_at(&offset.to_be_bytes(), 0)
+    }
+
+    /// Append a new page to the node arena, returning its offset.
+    fn alloc_page(&mut self, node: &Node) -> Result<u64> {
+        let offset = self.base.len()? as u64;
+        self.base.append(&Self::encode(node)?)?;
+
+        Ok(offset)
+    }
+
+    /// Overwrite an existing page in place.
+    fn write_page(&mut self, offset: u64, node: &Node) -> Result<()> {
+        self.base.write_at(&Self::encode(node)?, offset as usize)
+    }
+
+    /// Read and decode the page at `offset`.
+    fn read_page(&mut self, offset: u64) -> Result<Node> {
+        let mut buf = vec![0u8; PAGE_SIZE];
+        self.base.read_at(&mut buf, offset as usize)?;
+
+        Self::decode(&buf)
+    }
+
+    /// Read a leaf slot's value out of the value arena.
+    fn read_value(&mut self, entry: &LeafEntry) -> Option<Value> {
+        let mut buf = vec![0u8; entry.value_len as usize];
+        self.base.read_at(&mut buf, entry.value_offset as usize).ok()?;
+
+        Value::unpack(&buf).ok().map(|(value, _)| value)
+    }
+
+    /// Number of keys held by a page.
+    fn entry_count(node: &Node) -> usize {
+        match node {
+            Node::Leaf(entries) => entries.len(),
+            Node::Internal(keys, _) => keys.len(),
+        }
+    }
+
+    /// Whether `node` must be split before another entry is inserted: either
+    /// it already holds [`MAX_ENTRIES`] keys, or its encoded payload is
+    /// already too close to [`PAGE_SIZE`] to safely grow by one more entry.
+    /// Large keys (e.g. `Key::TEXT`) can overflow a page well before
+    /// [`MAX_ENTRIES`] is reached, so both limits are checked.
+    ///
+    /// The margin left for that one more entry is sized for the worst case
+    /// a `Key` can produce: a maximal, 255-byte `Key::TEXT` made entirely of
+    /// `0x00` bytes doubles to 512 bytes under [`escape_string`]'s NUL
+    /// escaping plus its 2-byte terminator, plus a 1-byte type tag, a 2-byte
+    /// length prefix, and the 8+4-byte value pointer a leaf entry carries.
+    fn is_full(node: &Node) -> bool {
+        const MAX_KEY_BYTES: usize = 255;
+        const WORST_CASE_ENTRY: usize = 1 + (MAX_KEY_BYTES * 2 + 2) + 2 + 8 + 4;
+
+        Self::entry_count(node) >= MAX_ENTRIES
+            || Self::payload_len(node) + WORST_CASE_ENTRY > PAGE_SIZE - 4
+    }
+
+    /// The byte length of `node`'s encoded payload, not counting the
+    /// trailing zero padding or CRC that [`Self::encode`] adds.
+    fn payload_len(node: &Node) -> usize {
+        Self::build_payload(node).len()
+    }
+
+    /// Split a full page into a left and right half plus the separator key
+    /// to promote into the parent.
+    ///
+    /// A leaf's median key is copied into the right half and promoted as a
+    /// separator (B+tree-style, since only leaves carry values); an
+    /// internal page's median key is removed from both halves and promoted,
+    /// the classic B-tree split.
+    fn split_node(node: Node) -> (Key, Node, Node) {
+        match node {
+            Node::Leaf(entries) => {
+                let mid = entries.len() / 2;
+                let right = entries[mid..].to_vec();
+                let left = entries[..mid].to_vec();
+                let separator = right[0].key.clone();
+
+                (separator, Node::Leaf(left), Node::Leaf(right))
+            }
+            Node::Internal(keys, children) => {
+                let mid = keys.len() / 2;
+                let median = keys[mid].clone();
+
+                let left = Node::Internal(keys[..mid].to_vec(), children[..=mid].to_vec());
+                let right = Node::Internal(keys[mid + 1..].to_vec(), children[mid + 1..].to_vec());
+
+                (median, left, right)
+            }
+        }
+    }
+
+    /// Insert `key`/`(value_offset, value_len)` into the sub-tree rooted at
+    /// `offset`, which must not itself be full; pre-emptively split any full
+    /// child before descending into it.
+    fn insert_non_full(
+        &mut self,
+        offset: u64,
+        key: &Key,
+        value_offset: u64,
+        value_len: u32,
+    ) -> Result<()> {
+        match self.read_page(offset)? {
+            Node::Leaf(mut entries) => {
+                match entries.binary_search_by(|entry| entry.key.cmp(key)) {
+                    Ok(idx) => {
+                        entries[idx].value_offset = value_offset;
+                        entries[idx].value_len = value_len;
+                    }
+                    Err(idx) => entries.insert(
+                        idx,
+                        LeafEntry {
+                            key: key.clone(),
+                            value_offset,
+                            value_len,
+                        },
+                    ),
+                }
+
+                self.write_page(offset, &Node::Leaf(entries))
+            }
+            Node::Internal(mut keys, mut children) => {
+                let mut idx = keys.partition_point(|k| k <= key);
+                let mut child_offset = children[idx];
+                let child = self.read_page(child_offset)?;
+
+                if Self::is_full(&child) {
+                    let (median, left, right) = Self::split_node(child);
+
+                    self.write_page(child_offset, &left)?;
+                    let right_offset = self.alloc_page(&right)?;
+
+                    keys.insert(idx, median);
+                    children.insert(idx + 1, right_offset);
+                    self.write_page(offset, &Node::Internal(keys.clone(), children.clone()))?;
+
+                    idx = keys.partition_point(|k| k <= key);
+                    child_offset = children[idx];
+                }
+
+                self.insert_non_full(child_offset, key, value_offset, value_len)
+            }
+        }
+    }
+
+    /// Collect every `(key, value)` pair reachable from `offset` via an
+    /// in-order traversal, ascending by key.
+    fn collect_in_order(&mut self, offset: u64, out: &mut Vec<(Key, Value)>) {
+        let node = match self.read_page(offset) {
+            Ok(node) => node,
+            Err(err) => {
+                error!("failed to read btree page at {}: {:?}", offset, err);
+                return;
+            }
+        };
+
+        match node {
+            Node::Leaf(entries) => {
+                for entry in entries {
+                    if let Some(value) = self.read_value(&entry) {
+                        out.push((entry.key.clone(), value));
+                    }
+                }
+            }
+            Node::Internal(_, children) => {
+                for child in children {
+                    self.collect_in_order(child, out);
+                }
+            }
+        }
+    }
+
+    /// Every `(key, value)` pair currently in the tree, ascending by key.
+    fn all_pairs(&mut self) -> Vec<(Key, Value)> {
+        let root = match self.load_root() {
+            Ok(root) => root,
+            Err(err) => {
+                error!("failed to read btree root: {:?}", err);
+                return Vec::new();
+            }
+        };
+
+        let mut pairs = Vec::new();
+        if root != 0 {
+            self.collect_in_order(root, &mut pairs);
+        }
+
+        pairs
+    }
+
+    /// Build a page's payload bytes, without the trailing zero padding or
+    /// CRC that [`Self::encode`] adds.
+    fn build_payload(node: &Node) -> Vec<u8> {
+        let mut payload: Vec<u8> = Vec::new();
+
+        match node {
+            Node::Leaf(entries) => {
+                payload.push(0);
+                payload.extend_from_slice(&(entries.len() as u16).to_be_bytes());
+
+                for entry in entries {
+                    let key_bytes = entry.key.pack();
+
+                    payload.extend_from_slice(&(key_bytes.len() as u16).to_be_bytes());
+                    payload.extend_from_slice(&key_bytes);
+                    payload.extend_from_slice(&entry.value_offset.to_be_bytes());
+                    payload.extend_from_slice(&entry.value_len.to_be_bytes());
+                }
+            }
+            Node::Internal(keys, children) => {
+                payload.push(1);
+                payload.extend_from_slice(&(keys.len() as u16).to_be_bytes());
+
+                for child in children {
+                    payload.extend_from_slice(&child.to_be_bytes());
+                }
+                for key in keys {
+                    let key_bytes = key.pack();
+
+                    payload.extend_from_slice(&(key_bytes.len() as u16).to_be_bytes());
+                    payload.extend_from_slice(&key_bytes);
+                }
+            }
+        }
+
+        payload
+    }
+
+    /// Encode a page into its fixed [`PAGE_SIZE`] on-disk form, with a CRC
+    /// over the whole page stored in its last 4 bytes.
+    ///
+    /// Splitting is meant to keep every page under this limit (see
+    /// [`Self::is_full`]), but since a single oversized key/value pair could
+    /// still not fit on its own page, this returns `Err(Error::InvalidArgument)`
+    /// rather than panicking if the payload genuinely doesn't fit.
+    fn encode(node: &Node) -> Result<Vec<u8>> {
+        let mut payload = Self::build_payload(node);
+
+        if payload.len() > PAGE_SIZE - 4 {
+            return Err(Error::InvalidArgument);
+        }
+        payload.resize(PAGE_SIZE - 4, 0);
+
+        let crc = Crc::<u32>::new(&CRC_32_CKSUM).checksum(&payload);
+        payload.extend_from_slice(&crc.to_be_bytes());
+
+        Ok(payload)
+    }
+
+    /// Encode `node` and append it to the in-memory `body` a rebuild is
+    /// staging (see [`Layer::compact`]), returning the offset it lands at.
+    /// Used instead of [`Self::alloc_page`] while compacting so nothing is
+    /// written to disk until the whole rebuilt tree is known to fit.
+    fn flush_node(body: &mut Vec<u8>, node: &Node) -> Result<u64> {
+        let page = Self::encode(node)?;
+        let offset = 8 + body.len() as u64;
+        body.extend_from_slice(&page);
+
+        Ok(offset)
+    }
+
+    /// Decode a page produced by [`Self::encode`], rejecting a CRC mismatch.
+    fn decode(data: &[u8]) -> Result<Node> {
+        if data.len() != PAGE_SIZE {
+            return Err(Error::InvalidArgument);
+        }
+
+        let body = &data[..PAGE_SIZE - 4];
+        let stored_crc = u32::from_be_bytes(data[PAGE_SIZE - 4..].try_into().unwrap());
+        let crc = Crc::<u32>::new(&CRC_32_CKSUM).checksum(body);
+
+        if stored_crc != crc {
+            return Err(Error::InvalidArgument);
+        }
+
+        let flag = body[0];
+        let count = u16::from_be_bytes([body[1], body[2]]) as usize;
+        let mut pos = 3;
+
+        match flag {
+            0 => {
+                let mut entries = Vec::with_capacity(count);
+
+                for _ in 0..count {
+                    let key_len = Self::read_u16(body, pos)? as usize;
+                    pos += 2;
+                    let (key, _) = Key::unpack(body.get(pos..pos + key_len).ok_or(Error::InvalidArgument)?)?;
+                    pos += key_len;
+                    let value_offset = Self::read_u64(body, pos)?;
+                    pos += 8;
+                    let value_len = Self::read_u32(body, pos)?;
+                    pos += 4;
+
+                    entries.push(LeafEntry {
+                        key,
+                        value_offset,
+                        value_len,
+                    });
+                }
+
+                Ok(Node::Leaf(entries))
+            }
+            1 => {
+                let mut children = Vec::with_capacity(count + 1);
+                for _ in 0..count + 1 {
+                    children.push(Self::read_u64(body, pos)?);
+                    pos += 8;
+                }
+
+                let mut keys = Vec::with_capacity(count);
+                for _ in 0..count {
+                    let key_len = Self::read_u16(body, pos)? as usize;
+                    pos += 2;
+                    let (key, _) = Key::unpack(body.get(pos..pos + key_len).ok_or(Error::InvalidArgument)?)?;
+                    pos += key_len;
+
+                    keys.push(key);
+                }
+
+                Ok(Node::Internal(keys, children))
+            }
+            _ => Err(Error::InvalidArgument),
+        }
+    }
+
+    fn read_u16(data: &[u8], pos: usize) -> Result<u16> {
+        let bytes: [u8; 2] = data.get(pos..pos + 2).ok_or(Error::InvalidArgument)?.try_into().unwrap();
+
+        Ok(u16::from_be_bytes(bytes))
+    }
+
+    fn read_u32(data: &[u8], pos: usize) -> Result<u32> {
+        let bytes: [u8; 4] = data.get(pos..pos + 4).ok_or(Error::InvalidArgument)?.try_into().unwrap();
+
+        Ok(u32::from_be_bytes(bytes))
+    }
+
+    fn read_u64(data: &[u8], pos: usize) -> Result<u64> {
+        let bytes: [u8; 8] = data.get(pos..pos + 8).ok_or(Error::InvalidArgument)?.try_into().unwrap();
+
+        Ok(u64::from_be_bytes(bytes))
+    }
+}
+
+impl Layer for BTreeLayer {
+    /// Open Layer by the passed URL.
+    fn open(url: &Url) -> Result<Self> {
+        let domain: String = url.domain().unwrap_or_default().to_string();
+        let path: String = format!("{}{}", domain, url.path());
+        let base = FileBaseLayer::new(&path, Some((Self::TYPE, 0)))?;
+
+        let mut layer = Self { base, path };
+        layer.load_root()?;
+
+        Ok(layer)
+    }
+
+    // ======== the general methods ========
+    /// Get the value of the specified key, return None if the key does not exist.
+    /// Note that the value may return if marked as deleted.
+    fn get(&mut self, key: &Key) -> Option<Value> {
+        let mut offset = match self.load_root() {
+            Ok(offset) => offset,
+            Err(err) => {
+                error!("failed to read btree root: {:?}", err);
+                return None;
+            }
+        };
+
+        if offset == 0 {
+            return None;
+        }
+
+        loop {
+            let node = match self.read_page(offset) {
+                Ok(node) => node,
+                Err(err) => {
+                    error!("failed to read btree page at {}: {:?}", offset, err);
+                    return None;
+                }
+            };
+
+            match node {
+                Node::Leaf(entries) => {
+                    let entry = entries
+                        .binary_search_by(|entry| entry.key.cmp(key))
+                        .ok()
+                        .map(|idx| entries[idx].clone())?;
+
+                    return self.read_value(&entry);
+                }
+                Node::Internal(keys, children) => {
+                    offset = children[keys.partition_point(|k| k <= key)];
+                }
+            }
+        }
+    }
+
+    /// Set the value of the specified key, which may overwrite and return the old value
+    /// without any warning.
+    fn put(&mut self, key: &Key, value: Value) -> Option<Value> {
+        let old = self.get(key);
+        let value_bytes = value.pack();
+
+        let value_offset = match self.base.len() {
+            Ok(len) => len as u64,
+            Err(err) => {
+                error!("failed to read btree file length: {:?}", err);
+                return old;
+            }
+        };
+        if let Err(err) = self.base.append(&value_bytes) {
+            error!("failed to append btree value: {:?}", err);
+            return old;
+        }
+        let value_len = value_bytes.len() as u32;
+
+        let mut root = match self.load_root() {
+            Ok(root) => root,
+            Err(err) => {
+                error!("failed to read btree root: {:?}", err);
+                return old;
+            }
+        };
+
+        if root == 0 {
+            let leaf = Node::Leaf(vec![LeafEntry {
+                key: key.clone(),
+                value_offset,
+                value_len,
+            }]);
+
+            match self.alloc_page(&leaf).and_then(|offset| {
+                self.set_root(offset)?;
+                Ok(())
+            }) {
+                Ok(()) => {}
+                Err(err) => error!("failed to create btree root: {:?}", err),
+            }
+
+            return old;
+        }
+
+        match self.read_page(root) {
+            Ok(root_node) if Self::is_full(&root_node) => {
+                let (median, left, right) = Self::split_node(root_node);
+
+                if let Err(err) = self.write_page(root, &left) {
+                    error!("failed to split btree root: {:?}", err);
+                    return old;
+                }
+
+                let right_offset = match self.alloc_page(&right) {
+                    Ok(offset) => offset,
+                    Err(err) => {
+                        error!("failed to split btree root: {:?}", err);
+                        return old;
+                    }
+                };
+
+                let new_root = Node::Internal(vec![median], vec![root, right_offset]);
+                match self.alloc_page(&new_root) {
+                    Ok(offset) => {
+                        if let Err(err) = self.set_root(offset) {
+                            error!("failed to set btree root: {:?}", err);
+                            return old;
+                        }
+                        root = offset;
+                    }
+                    Err(err) => {
+                        error!("failed to grow btree root: {:?}", err);
+                        return old;
+                    }
+                }
+            }
+            Ok(_) => {}
+            Err(err) => {
+                error!("failed to read btree root page: {:?}", err);
+                return old;
+            }
+        }
+
+        if let Err(err) = self.insert_non_full(root, key, value_offset, value_len) {
+            error!("failed to insert into btree: {:?}", err);
+        }
+
+        old
+    }
+
+    /// Delete the value of the specified key, which may not actually delete the value
+    /// but mark it as deleted.
+    fn del(&mut self, key: &Key) {
+        let _ = self.put(key, Value::DELETED);
+    }
+
+    // ======== the iteration methods ========
+    /// Iterate over the key-value pairs in the layer which the order is not guaranteed.
+    fn iter(&mut self) -> Box<dyn Iterator<Item = (Key, Value)> + '_> {
+        Box::new(self.all_pairs().into_iter())
+    }
+
+    /// Iterate over the key-value pairs with the ascending order of the key, pass the optional
+    /// based key to start the iteration.
+    fn forward<'a>(
+        &'a mut self,
+        base: Option<&'a Key>,
+    ) -> Box<dyn Iterator<Item = (Key, Value)> + '_> {
+        let mut pairs = self.all_pairs();
+        pairs.retain(|(key, _)| match base {
+            Some(base) => key >= base,
+            None => true,
+        });
+
+        Box::new(pairs.into_iter())
+    }
+
+    /// Iterate over the key-value pairs with the descending order of the key, pass the optional
+    /// based key to start the iteration.
+    fn backward<'a>(
+        &'a mut self,
+        base: Option<&'a Key>,
+    ) -> Box<dyn Iterator<Item = (Key, Value)> + '_> {
+        let mut pairs = self.all_pairs();
+        pairs.retain(|(key, _)| match base {
+            Some(base) => key <= base,
+            None => true,
+        });
+        pairs.reverse();
+
+        Box::new(pairs.into_iter())
+    }
+
+    // ======== the authenticated methods ========
+    /// Remove the existing data and files. The layer may not be initialized until any
+    /// general method is called.
+    fn unlink(&mut self) {
+        self.base.unlink();
+    }
+
+    /// Drop every tombstoned key and rebuild a balanced tree bottom-up from
+    /// the surviving, sorted entries.
+    ///
+    /// The whole rebuilt data section (values, leaves, and internal pages)
+    /// is staged in an in-memory `body` first, sized with [`Self::is_full`]
+    /// just like a live [`Layer::put`] would, and the existing file is only
+    /// unlinked once that staged rebuild is known to have fully succeeded
+    /// (mirroring [`super::sstable::SSTableLayer::compact`]). That way a
+    /// page that can't be packed mid-rebuild leaves the original file
+    /// untouched instead of losing data.
+    fn compact(&mut self) {
+        let mut live = self.all_pairs();
+        live.retain(|(_, value)| *value != Value::DELETED);
+
+        let mut body: Vec<u8> = Vec::new();
+        let mut level: Vec<(Key, u64)> = Vec::new();
+
+        // bottom-up: pack the sorted entries into size-aware leaves first.
+        let mut entries: Vec<LeafEntry> = Vec::new();
+        for (key, value) in &live {
+            let value_bytes = value.pack();
+            let value_offset = 8 + body.len() as u64;
+            body.extend_from_slice(&value_bytes);
+
+            entries.push(LeafEntry {
+                key: key.clone(),
+                value_offset,
+                value_len: value_bytes.len() as u32,
+            });
+
+            if Self::is_full(&Node::Leaf(entries.clone())) {
+                let first_key = entries[0].key.clone();
+                match Self::flush_node(&mut body, &Node::Leaf(std::mem::take(&mut entries))) {
+                    Ok(offset) => level.push((first_key, offset)),
+                    Err(err) => {
+                        error!("failed to encode btree leaf during compact: {:?}", err);
+                        return;
+                    }
+                }
+            }
+        }
+        if !entries.is_empty() {
+            let first_key = entries[0].key.clone();
+            match Self::flush_node(&mut body, &Node::Leaf(entries)) {
+                Ok(offset) => level.push((first_key, offset)),
+                Err(err) => {
+                    error!("failed to encode btree leaf during compact: {:?}", err);
+                    return;
+                }
+            }
+        }
+
+        // then keep leveling internal pages up until a single root remains,
+        // packing each as large as is_full() allows.
+        while level.len() > 1 {
+            let mut next: Vec<(Key, u64)> = Vec::new();
+            let mut iter = level.into_iter();
+            let mut first = match iter.next() {
+                Some(pair) => pair,
+                None => break,
+            };
+
+            loop {
+                let first_key = first.0.clone();
+                let mut keys: Vec<Key> = Vec::new();
+                let mut children: Vec<u64> = vec![first.1];
+
+                for (key, offset) in iter.by_ref() {
+                    keys.push(key);
+                    children.push(offset);
+
+                    if Self::is_full(&Node::Internal(keys.clone(), children.clone())) {
+                        break;
+                    }
+                }
+
+                match Self::flush_node(&mut body, &Node::Internal(keys, children)) {
+                    Ok(offset) => next.push((first_key, offset)),
+                    Err(err) => {
+                        error!("failed to encode btree node during compact: {:?}", err);
+                        return;
+                    }
+                }
+
+                first = match iter.next() {
+                    Some(pair) => pair,
+                    None => break,
+                };
+            }
+
+            level = next;
+        }
+
+        let root_offset = level.into_iter().next().map(|(_, offset)| offset).unwrap_or(0);
+        let mut file: Vec<u8> = Vec::new();
+        file.extend_from_slice(&root_offset.to_be_bytes());
+        file.extend_from_slice(&body);
+
+        self.base.unlink();
+        match FileBaseLayer::new(&self.path, Some((Self::TYPE, 0))) {
+            Ok(base) => self.base = base,
+            Err(err) => {
+                error!("failed to reopen btree at {}: {:?}", self.path, err);
+                return;
+            }
+        }
+
+        if let Err(err) = self.base.append(&file) {
+            error!("failed to write compacted btree: {:?}", err);
+        }
+    }
+
+    // ======== the interchange methods ========
+    fn archive_meta(&self) -> (u8, u16) {
+        (self.base.typ(), self.base.flags())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestContext {
+        path: String,
+    }
+
+    impl TestContext {
+        fn new(path: &str) -> Self {
+            Self {
+                path: path.to_string(),
+            }
+        }
+    }
+
+    impl Drop for TestContext {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+
+    #[test]
+    fn test_btree_put_and_get() {
+        let file = "test_btree_put_and_get";
+        let _ctx = TestContext::new(file);
+        let url = Url::parse(&format!("btree://{}", file)).unwrap();
+
+        let mut layer = BTreeLayer::open(&url).unwrap();
+        let key: Key = "key".into();
+        let value: Value = "value".into();
+
+        layer.put(&key, value.clone());
+        assert_eq!(layer.get(&key), Some(value));
+    }
+
+    #[test]
+    fn test_btree_get_missing_key_is_none() {
+        let file = "test_btree_get_missing_key_is_none";
+        let _ctx = TestContext::new(file);
+        let url = Url::parse(&format!("btree://{}", file)).unwrap();
+
+        let mut layer = BTreeLayer::open(&url).unwrap();
+        assert_eq!(layer.get(&"missing".into()), None);
+    }
+
+    #[test]
+    fn test_btree_put_and_del() {
+        let file = "test_btree_put_and_del";
+        let _ctx = TestContext::new(file);
+        let url = Url::parse(&format!("btree://{}", file)).unwrap();
+
+        let mut layer = BTreeLayer::open(&url).unwrap();
+        let key: Key = "key".into();
+
+        layer.put(&key, "value".into());
+        layer.del(&key);
+
+        assert_eq!(layer.get(&key), Some(Value::DELETED));
+    }
+
+    #[test]
+    fn test_btree_splits_across_many_keys() {
+        let file = "test_btree_splits_across_many_keys";
+        let _ctx = TestContext::new(file);
+        let url = Url::parse(&format!("btree://{}", file)).unwrap();
+
+        let mut layer = BTreeLayer::open(&url).unwrap();
+        let size = MAX_ENTRIES * 8;
+
+        for index in 0..size {
+            let key: Key = index.into();
+            layer.put(&key, format!("value {}", index).into());
+        }
+
+        for index in 0..size {
+            let key: Key = index.into();
+            assert_eq!(layer.get(&key), Some(format!("value {}", index).into()));
+        }
+
+        assert_eq!(layer.iter().count(), size);
+    }
+
+    #[test]
+    fn test_btree_forward_and_backward_are_sorted() {
+        let file = "test_btree_forward_and_backward_are_sorted";
+        let _ctx = TestContext::new(file);
+        let url = Url::parse(&format!("btree://{}", file)).unwrap();
+
+        let mut layer = BTreeLayer::open(&url).unwrap();
+        let size = MAX_ENTRIES * 3;
+
+        for index in 0..size {
+            let key: Key = index.into();
+            layer.put(&key, "v".into());
+        }
+
+        let forward: Vec<Key> = layer.forward(None).map(|(key, _)| key).collect();
+        assert_eq!(forward.len(), size);
+        assert!(forward.windows(2).all(|w| w[0] < w[1]));
+
+        let backward: Vec<Key> = layer.backward(None).map(|(key, _)| key).collect();
+        assert_eq!(backward.len(), size);
+        assert!(backward.windows(2).all(|w| w[0] > w[1]));
+    }
+
+    #[test]
+    fn test_btree_compact_drops_tombstones_and_rebuilds() {
+        let file = "test_btree_compact_drops_tombstones_and_rebuilds";
+        let _ctx = TestContext::new(file);
+        let url = Url::parse(&format!("btree://{}", file)).unwrap();
+
+        let mut layer = BTreeLayer::open(&url).unwrap();
+        let size = MAX_ENTRIES * 4;
+
+        for index in 0..size {
+            let key: Key = index.into();
+            layer.put(&key, "v".into());
+        }
+        layer.del(&0usize.into());
+        layer.compact();
+
+        assert_eq!(layer.get(&0usize.into()), None);
+        for index in 1..size {
+            let key: Key = index.into();
+            assert_eq!(layer.get(&key), Some("v".into()));
+        }
+
+        let forward: Vec<Key> = layer.forward(None).map(|(key, _)| key).collect();
+        assert_eq!(forward.len(), size - 1);
+        assert!(forward.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn test_btree_survives_reopen() {
+        let file = "test_btree_survives_reopen";
+        let _ctx = TestContext::new(file);
+        let url = Url::parse(&format!("btree://{}", file)).unwrap();
+
+        let mut layer = BTreeLayer::open(&url).unwrap();
+        for index in 0..(MAX_ENTRIES * 2) {
+            let key: Key = index.into();
+            layer.put(&key, "v".into());
+        }
+        drop(layer);
+
+        let mut reopened = BTreeLayer::open(&url).unwrap();
+        for index in 0..(MAX_ENTRIES * 2) {
+            let key: Key = index.into();
+            assert_eq!(reopened.get(&key), Some("v".into()));
+        }
+    }
+}
@@ -7,6 +7,21 @@ pub trait FileLayer {
     const TYPE: u8;
 }
 
+/// The result of scanning a file's framed data section, as produced by
+/// [`FileBaseLayer::check`]/[`FileBaseLayer::repair`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CheckReport {
+    /// how many well-formed records were scanned before stopping.
+    pub records: usize,
+
+    /// total bytes of known-good records (frame + payload); where
+    /// [`FileBaseLayer::repair`] truncates the data section back to.
+    pub bytes: usize,
+
+    /// the offset of the first corrupt or truncated record, if any.
+    pub first_bad_offset: Option<usize>,
+}
+
 /// The basic file layer implementation for Papyrus.
 ///
 /// This layer is designed to store key-value pairs in local and single file,
@@ -44,6 +59,12 @@ pub struct FileBaseLayer {
     ver: Option<u8>,
     typ: Option<u8>,
     flags: Option<u16>,
+
+    /// rotate the active file once `append` would push the data section
+    /// past this many bytes. `None` disables rotation.
+    max_size: Option<u64>,
+    /// how many rotated backups (`path.1`, `path.2`, ...) to retain.
+    max_files: u32,
 }
 
 #[allow(dead_code)]
@@ -64,12 +85,142 @@ impl FileBaseLayer {
             typ: None,
             flags: None,
             ver: None,
+            max_size: None,
+            max_files: 0,
         };
 
         layer.open(meta)?;
         Ok(layer)
     }
 
+    /// Create a new file layer with a size-triggered rotation policy.
+    ///
+    /// Once an [`Self::append`] would push the data section past `max_size`
+    /// bytes, the active file is rotated into `path.1`, existing backups
+    /// shift up (`path.1` → `path.2`, ...) and the oldest beyond `max_files`
+    /// is dropped, before a fresh file carrying the same header metadata
+    /// picks up new writes. Each segment keeps its own 16-byte header and
+    /// lock, so it remains independently verifiable. See [`Self::segments`]
+    /// to read the full logical data section back in order.
+    pub fn new_with_rotation(
+        path: &str,
+        meta: Option<(u8, u16)>,
+        max_size: u64,
+        max_files: u32,
+    ) -> Result<Self> {
+        let mut layer = Self::new(path, meta)?;
+        layer.max_size = Some(max_size);
+        layer.max_files = max_files;
+
+        Ok(layer)
+    }
+
+    /// List the rotated backups plus the active file, oldest first, so a
+    /// reader can reconstruct the full logical data section in order.
+    pub fn segments(&self) -> Vec<std::path::PathBuf> {
+        let mut segments = Vec::new();
+
+        let mut n = self.max_files;
+        while n >= 1 {
+            let path = self.segment_path(n);
+            if path.exists() {
+                segments.push(path);
+            }
+            n -= 1;
+        }
+
+        segments.push(self.path.clone());
+        segments
+    }
+
+    /// Append a CRC-checked record to the data section, framed as
+    /// `[len: u32][crc32: u32][payload]`.
+    pub fn append_record(&mut self, payload: &[u8]) -> Result<()> {
+        let crc = Self::checksum(payload);
+
+        let mut frame = Vec::with_capacity(8 + payload.len());
+        frame.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+        frame.extend_from_slice(&crc.to_be_bytes());
+        frame.extend_from_slice(payload);
+
+        self.append(&frame)
+    }
+
+    /// Read the record framed at `offset`, verifying its CRC.
+    pub fn read_record(&mut self, offset: usize) -> Result<Vec<u8>> {
+        let mut head = [0u8; 8];
+        self.read_at(&mut head, offset)?;
+
+        let len = u32::from_be_bytes([head[0], head[1], head[2], head[3]]) as usize;
+        let crc = u32::from_be_bytes([head[4], head[5], head[6], head[7]]);
+
+        let mut payload = vec![0u8; len];
+        self.read_at(&mut payload, offset + 8)?;
+
+        if Self::checksum(&payload) != crc {
+            warn!("corrupt record at offset {}: checksum mismatch", offset);
+            return Err(Error::InvalidArgument);
+        }
+
+        Ok(payload)
+    }
+
+    /// Scan every framed record in the data section, reporting how many
+    /// scanned clean and, if a record is truncated or fails its CRC, the
+    /// offset it broke at.
+    pub fn check(&mut self) -> Result<CheckReport> {
+        let total = self.len()?;
+        let mut report = CheckReport::default();
+        let mut offset = 0;
+
+        while offset < total {
+            if offset + 8 > total {
+                report.first_bad_offset = Some(offset);
+                break;
+            }
+
+            let mut head = [0u8; 8];
+            self.read_at(&mut head, offset)?;
+
+            let len = u32::from_be_bytes([head[0], head[1], head[2], head[3]]) as usize;
+            let crc = u32::from_be_bytes([head[4], head[5], head[6], head[7]]);
+
+            if offset + 8 + len > total {
+                report.first_bad_offset = Some(offset);
+                break;
+            }
+
+            let mut payload = vec![0u8; len];
+            self.read_at(&mut payload, offset + 8)?;
+
+            if Self::checksum(&payload) != crc {
+                report.first_bad_offset = Some(offset);
+                break;
+            }
+
+            report.records += 1;
+            report.bytes += 8 + len;
+            offset += 8 + len;
+        }
+
+        Ok(report)
+    }
+
+    /// Walk the data section like [`Self::check`] and, if it finds a
+    /// corrupt or truncated record, truncate the file at the last good
+    /// record boundary and rewrite a clean header. Analogous to the
+    /// check/repair tooling for other structured layers: validate every
+    /// node, then rebuild to the last good point.
+    pub fn repair(&mut self) -> Result<CheckReport> {
+        let report = self.check()?;
+
+        if report.first_bad_offset.is_some() {
+            self.truncate(report.bytes)?;
+        }
+
+        Ok(report)
+    }
+
     /// Get the type of the file.
     pub fn typ(&self) -> u8 {
         self.typ.expect("file layer not opened")
@@ -110,12 +261,35 @@ impl FileBaseLayer {
         Ok(())
     }
 
-    /// Write data into the end of data section.
+    /// Get the length of the data section, i.e. the file size excluding the
+    /// 16-byte header. Useful for callers that need to know the offset an
+    /// [`Self::append`] will land at before making it.
+    pub fn len(&mut self) -> Result<usize> {
+        // re-open the current file
+        let meta = (self.typ(), self.flags());
+        self.open(Some(meta))?;
+
+        let file = self.file.as_mut().expect("file layer not opened");
+        let total = file.metadata()?.len() as usize;
+
+        Ok(total.saturating_sub(Self::HEADER_SIZE))
+    }
+
+    /// Write data into the end of data section. If a rotation policy was
+    /// set via [`Self::new_with_rotation`] and this write would push the
+    /// data section past `max_size`, the active file is rotated first.
     pub fn append(&mut self, buff: &[u8]) -> Result<()> {
         // re-open the current file
         let meta = (self.typ(), self.flags());
         self.open(Some(meta))?;
 
+        if let Some(max_size) = self.max_size {
+            let len = self.len()? as u64 + buff.len() as u64;
+            if len > max_size {
+                self.rotate()?;
+            }
+        }
+
         let file = self.file.as_mut().expect("file layer not opened");
         let _ = file.seek(SeekFrom::End(0))?;
 
@@ -157,6 +331,55 @@ impl FileBaseLayer {
 
         Ok(())
     }
+
+    /// Check whether `path`'s header records a lock, without opening it
+    /// through the usual [`Self::new`] path. Returns `Ok(())` when the file
+    /// is unlocked (or does not exist yet), [`Error::Locked`] when a still-
+    /// running process holds it, and [`Error::StaleLock`] when the owning
+    /// PID has died without releasing it.
+    ///
+    /// This mirrors the repair path in metadata tooling: a stale owner must
+    /// be detected here before an operator decides to call
+    /// [`Self::force_unlock`].
+    pub fn check_lock(path: &str) -> Result<()> {
+        let mut file = match std::fs::File::open(path) {
+            Ok(file) => file,
+            Err(_) => return Ok(()),
+        };
+
+        let mut header = [0u8; Self::HEADER_SIZE];
+        if file.read_exact(&mut header).is_err() {
+            return Ok(());
+        }
+
+        match Self::header_owner(header) {
+            0 => Ok(()),
+            owner if Self::process_alive(owner) => Err(Error::Locked),
+            _ => Err(Error::StaleLock),
+        }
+    }
+
+    /// Forcibly clear the owning PID recorded in `path`'s header, regardless
+    /// of whether that owner is still alive. Intended for operator recovery
+    /// when [`Self::open`]'s own liveness check cannot run, e.g. the PID was
+    /// already reused by an unrelated process; confirm no legitimate holder
+    /// is still running before calling this.
+    pub fn force_unlock(path: &str) -> Result<()> {
+        let mut file = std::fs::OpenOptions::new().read(true).write(true).open(path)?;
+
+        let mut header = [0u8; Self::HEADER_SIZE];
+        file.seek(SeekFrom::Start(0))?;
+        file.read_exact(&mut header)?;
+
+        header[8..12].copy_from_slice(&0u32.to_be_bytes());
+        let checksum = Self::checksum(&header[0..12]);
+        header[12..16].copy_from_slice(&checksum.to_be_bytes());
+
+        file.seek(SeekFrom::Start(0))?;
+        file.write_all(&header)?;
+
+        Ok(())
+    }
 }
 
 /// The private methods of FileBaseLayer
@@ -224,6 +447,10 @@ impl FileBaseLayer {
 
     /// Lock the current file with the PID of the current process.
     /// It modify the file header and erase when the process exits.
+    ///
+    /// If the header records a PID that is no longer running, the crashed
+    /// owner never released the lock, so it's treated as free and reclaimed
+    /// here rather than permanently wedging every future `open`.
     fn lock(&mut self) -> Result<()> {
         let pid: u32 = std::process::id();
         if self.locked(pid) {
@@ -231,6 +458,11 @@ impl FileBaseLayer {
             return Err(Error::Locked);
         }
 
+        let owner = self.owner_pid();
+        if owner != 0 && owner != pid {
+            warn!("reclaiming lock held by crashed process {}", owner);
+        }
+
         let header = FileBaseLayer::header(self.typ(), self.flags(), true);
         let file = self.file();
 
@@ -253,23 +485,121 @@ impl FileBaseLayer {
         Ok(())
     }
 
-    /// check file locked by the current process.
+    /// check file locked against `pid` by a still-running owner. A PID
+    /// recorded in the header that is no longer alive does not count as
+    /// locked, since its crashed owner can never release it.
     fn locked(&mut self, pid: u32) -> bool {
+        let owner = self.owner_pid();
+
+        owner != 0 && owner != pid && Self::process_alive(owner)
+    }
+
+    /// Read the owning PID recorded in the current header, `0` if unlocked.
+    fn owner_pid(&mut self) -> u32 {
         let mut header = [0u8; Self::HEADER_SIZE];
         let file = self.file();
 
         file.seek(SeekFrom::Start(0)).expect("seek file failed");
-        let resp = match file.read_exact(&mut header) {
-            Ok(_) => {
-                let locked_pid: u32 =
-                    u32::from_be_bytes([header[8], header[9], header[10], header[11]]);
+        match file.read_exact(&mut header) {
+            Ok(_) => Self::header_owner(header),
+            Err(_) => 0,
+        }
+    }
 
-                !(locked_pid == 0 || pid == locked_pid)
-            }
-            Err(_) => false,
+    /// Extract the owning PID out of a raw header buffer.
+    fn header_owner(header: [u8; Self::HEADER_SIZE]) -> u32 {
+        u32::from_be_bytes([header[8], header[9], header[10], header[11]])
+    }
+
+    /// Check whether `pid` is still a running process.
+    #[cfg(unix)]
+    fn process_alive(pid: u32) -> bool {
+        // sends no signal, but still performs the existence/permission
+        // check: 0 means alive, ESRCH means gone, EPERM means alive but
+        // owned by someone else.
+        if unsafe { libc::kill(pid as libc::pid_t, 0) } == 0 {
+            return true;
+        }
+
+        std::io::Error::last_os_error().raw_os_error() != Some(libc::ESRCH)
+    }
+
+    /// Check whether `pid` is still a running process.
+    #[cfg(windows)]
+    fn process_alive(pid: u32) -> bool {
+        use windows_sys::Win32::Foundation::CloseHandle;
+        use windows_sys::Win32::System::Threading::{
+            OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION,
         };
 
-        resp
+        unsafe {
+            let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+            if handle == 0 {
+                return false;
+            }
+
+            CloseHandle(handle);
+        }
+
+        true
+    }
+
+    /// The path of the `n`-th rotated backup, or the active path itself
+    /// when `n` is `0`.
+    fn segment_path(&self, n: u32) -> std::path::PathBuf {
+        if n == 0 {
+            return self.path.clone();
+        }
+
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".{}", n));
+
+        std::path::PathBuf::from(name)
+    }
+
+    /// Rotate the active file into the first backup slot, shifting existing
+    /// backups up and dropping the oldest beyond `max_files`, then start a
+    /// fresh file carrying the same header metadata.
+    fn rotate(&mut self) -> Result<()> {
+        self.close()?;
+
+        if self.max_files == 0 {
+            std::fs::remove_file(&self.path)?;
+        } else {
+            let _ = std::fs::remove_file(self.segment_path(self.max_files));
+
+            let mut n = self.max_files;
+            while n > 1 {
+                let from = self.segment_path(n - 1);
+                if from.exists() {
+                    std::fs::rename(&from, self.segment_path(n))?;
+                }
+                n -= 1;
+            }
+
+            std::fs::rename(&self.path, self.segment_path(1))?;
+        }
+
+        let meta = (self.typ(), self.flags());
+        self.open(Some(meta))?;
+
+        Ok(())
+    }
+
+    /// Truncate the data section back to `len` bytes and rewrite a clean
+    /// header, used by [`Self::repair`] to drop everything past the last
+    /// good record.
+    fn truncate(&mut self, len: usize) -> Result<()> {
+        self.close()?;
+
+        let file = std::fs::OpenOptions::new().write(true).open(&self.path)?;
+        file.set_len((Self::HEADER_SIZE + len) as u64)?;
+        drop(file);
+
+        let meta = (self.typ(), self.flags());
+        self.open(Some(meta))?;
+
+        Ok(())
     }
 
     /// Change the current position of file descriptor.
@@ -378,6 +708,27 @@ mod tests {
         }
     }
 
+    /// Overwrite `file`'s header to claim it's locked by `pid`, fixing up
+    /// the checksum the same way [`FileBaseLayer::header`] would.
+    fn write_header_pid(file: &str, pid: u32) {
+        let mut header = [0u8; FileBaseLayer::HEADER_SIZE];
+        std::fs::File::open(file)
+            .unwrap()
+            .read_exact(&mut header)
+            .unwrap();
+
+        header[8..12].copy_from_slice(&pid.to_be_bytes());
+        let checksum = FileBaseLayer::checksum(&header[0..12]);
+        header[12..16].copy_from_slice(&checksum.to_be_bytes());
+
+        std::fs::OpenOptions::new()
+            .write(true)
+            .open(file)
+            .unwrap()
+            .write_all(&header)
+            .unwrap();
+    }
+
     #[test]
     fn test_create_file_layer() {
         let typ: u8 = 1;
@@ -463,6 +814,161 @@ mod tests {
         assert_eq!(buff, data);
     }
 
+    #[test]
+    fn test_append_rotates_when_max_size_exceeded() {
+        let typ: u8 = 1;
+        let flags: u16 = 0x1234;
+
+        let file = "test_append_rotates_when_max_size_exceeded";
+        let mut layer = FileBaseLayer::new_with_rotation(file, Some((typ, flags)), 4, 2).unwrap();
+
+        assert_eq!(layer.append(&[0x01, 0x02, 0x03, 0x04]), Ok(()));
+        assert_eq!(layer.len(), Ok(4));
+
+        // this append would push the data section past max_size, so the
+        // active file rotates into a backup before the write lands.
+        assert_eq!(layer.append(&[0x05]), Ok(()));
+        assert_eq!(layer.len(), Ok(1));
+        assert_eq!(layer.segment_path(1).exists(), true);
+
+        std::fs::remove_file(file).unwrap();
+        std::fs::remove_file(layer.segment_path(1)).unwrap();
+    }
+
+    #[test]
+    fn test_rotate_drops_the_oldest_backup_beyond_max_files() {
+        let typ: u8 = 1;
+        let flags: u16 = 0x1234;
+
+        let file = "test_rotate_drops_the_oldest_backup_beyond_max_files";
+        let mut layer = FileBaseLayer::new_with_rotation(file, Some((typ, flags)), 1, 1).unwrap();
+
+        assert_eq!(layer.append(&[0x01]), Ok(())); // fills the first file
+        assert_eq!(layer.append(&[0x02]), Ok(())); // rotates: file -> file.1
+        assert_eq!(layer.append(&[0x03]), Ok(())); // rotates again, dropping file.1's old content
+
+        assert_eq!(layer.segment_path(1).exists(), true);
+        assert_eq!(layer.segment_path(2).exists(), false);
+
+        std::fs::remove_file(file).unwrap();
+        std::fs::remove_file(layer.segment_path(1)).unwrap();
+    }
+
+    #[test]
+    fn test_segments_lists_the_backups_and_active_file_oldest_first() {
+        let typ: u8 = 1;
+        let flags: u16 = 0x1234;
+
+        let file = "test_segments_lists_the_backups_and_active_file_oldest_first";
+        let mut layer = FileBaseLayer::new_with_rotation(file, Some((typ, flags)), 1, 2).unwrap();
+
+        assert_eq!(layer.append(&[0x01]), Ok(()));
+        assert_eq!(layer.append(&[0x02]), Ok(())); // rotates: file -> file.1
+        assert_eq!(layer.append(&[0x03]), Ok(())); // rotates: file.1 -> file.2, file -> file.1
+
+        let segments = layer.segments();
+        assert_eq!(
+            segments,
+            vec![layer.segment_path(2), layer.segment_path(1), layer.path.clone()]
+        );
+
+        for segment in &segments {
+            std::fs::remove_file(segment).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_append_record_and_read_record_roundtrip() {
+        let typ: u8 = 1;
+        let flags: u16 = 0x1234;
+
+        let file = "test_append_record_and_read_record_roundtrip";
+        let mut ctx = TestContext::new(file, Some((typ, flags)));
+
+        assert_eq!(ctx.layer.append_record(b"hello"), Ok(()));
+        assert_eq!(ctx.layer.append_record(b"world!"), Ok(()));
+
+        assert_eq!(ctx.layer.read_record(0), Ok(b"hello".to_vec()));
+        assert_eq!(ctx.layer.read_record(8 + 5), Ok(b"world!".to_vec()));
+    }
+
+    #[test]
+    fn test_read_record_detects_a_corrupt_payload() {
+        let typ: u8 = 1;
+        let flags: u16 = 0x1234;
+
+        let file = "test_read_record_detects_a_corrupt_payload";
+        let mut ctx = TestContext::new(file, Some((typ, flags)));
+
+        assert_eq!(ctx.layer.append_record(b"hello"), Ok(()));
+
+        // flip a byte of the payload without touching its crc.
+        ctx.layer.write_at(b"X", 8).unwrap();
+
+        assert_eq!(ctx.layer.read_record(0), Err(Error::InvalidArgument));
+    }
+
+    #[test]
+    fn test_check_reports_every_clean_record() {
+        let typ: u8 = 1;
+        let flags: u16 = 0x1234;
+
+        let file = "test_check_reports_every_clean_record";
+        let mut ctx = TestContext::new(file, Some((typ, flags)));
+
+        assert_eq!(ctx.layer.append_record(b"hello"), Ok(()));
+        assert_eq!(ctx.layer.append_record(b"world!"), Ok(()));
+
+        let report = ctx.layer.check().unwrap();
+        assert_eq!(report.records, 2);
+        assert_eq!(report.bytes, (8 + 5) + (8 + 6));
+        assert_eq!(report.first_bad_offset, None);
+    }
+
+    #[test]
+    fn test_check_stops_at_the_first_corrupt_record() {
+        let typ: u8 = 1;
+        let flags: u16 = 0x1234;
+
+        let file = "test_check_stops_at_the_first_corrupt_record";
+        let mut ctx = TestContext::new(file, Some((typ, flags)));
+
+        assert_eq!(ctx.layer.append_record(b"hello"), Ok(()));
+        let second_offset = 8 + 5;
+        assert_eq!(ctx.layer.append_record(b"world!"), Ok(()));
+
+        ctx.layer.write_at(b"X", second_offset + 8).unwrap();
+
+        let report = ctx.layer.check().unwrap();
+        assert_eq!(report.records, 1);
+        assert_eq!(report.bytes, 8 + 5);
+        assert_eq!(report.first_bad_offset, Some(second_offset));
+    }
+
+    #[test]
+    fn test_repair_truncates_to_the_last_good_record() {
+        let typ: u8 = 1;
+        let flags: u16 = 0x1234;
+
+        let file = "test_repair_truncates_to_the_last_good_record";
+        let mut ctx = TestContext::new(file, Some((typ, flags)));
+
+        assert_eq!(ctx.layer.append_record(b"hello"), Ok(()));
+        let second_offset = 8 + 5;
+        assert_eq!(ctx.layer.append_record(b"world!"), Ok(()));
+
+        ctx.layer.write_at(b"X", second_offset + 8).unwrap();
+
+        let report = ctx.layer.repair().unwrap();
+        assert_eq!(report.records, 1);
+        assert_eq!(report.first_bad_offset, Some(second_offset));
+
+        // the corrupt record is gone and the layer re-opens cleanly.
+        assert_eq!(ctx.layer.len(), Ok(second_offset));
+        assert_eq!(ctx.layer.read_record(0), Ok(b"hello".to_vec()));
+        assert_eq!(ctx.layer.check(), Ok(CheckReport { records: 1, bytes: second_offset, first_bad_offset: None }));
+    }
+
     #[test]
     fn test_file_locked() {
         let typ: u8 = 1;
@@ -497,4 +1003,73 @@ mod tests {
         assert_eq!(layer.locked(pid), false);
         assert_eq!(layer.locked(0), true);
     }
+
+    #[test]
+    fn test_check_lock_on_missing_file_is_ok() {
+        assert_eq!(
+            FileBaseLayer::check_lock("test_check_lock_on_missing_file_is_ok"),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_check_lock_reports_stale_owner() {
+        let typ: u8 = 1;
+        let flags: u16 = 0x1234;
+
+        let file = "test_check_lock_reports_stale_owner";
+        let ctx = TestContext::new(file, Some((typ, flags)));
+        // simulate a crash: skip Drop/unlock so the PID stays in the header
+        std::mem::forget(ctx);
+
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        let dead_pid = child.id();
+        child.wait().unwrap();
+        write_header_pid(file, dead_pid);
+
+        assert_eq!(FileBaseLayer::check_lock(file), Err(Error::StaleLock));
+
+        std::fs::remove_file(file).unwrap();
+    }
+
+    #[test]
+    fn test_force_unlock_clears_a_stale_pid() {
+        let typ: u8 = 1;
+        let flags: u16 = 0x1234;
+
+        let file = "test_force_unlock_clears_a_stale_pid";
+        let ctx = TestContext::new(file, Some((typ, flags)));
+        std::mem::forget(ctx);
+
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        let dead_pid = child.id();
+        child.wait().unwrap();
+        write_header_pid(file, dead_pid);
+
+        assert_eq!(FileBaseLayer::check_lock(file), Err(Error::StaleLock));
+        assert_eq!(FileBaseLayer::force_unlock(file), Ok(()));
+        assert_eq!(FileBaseLayer::check_lock(file), Ok(()));
+
+        std::fs::remove_file(file).unwrap();
+    }
+
+    #[test]
+    fn test_open_reclaims_a_stale_lock_transparently() {
+        let typ: u8 = 1;
+        let flags: u16 = 0x1234;
+
+        let file = "test_open_reclaims_a_stale_lock_transparently";
+        let ctx = TestContext::new(file, Some((typ, flags)));
+        std::mem::forget(ctx);
+
+        let mut child = std::process::Command::new("true").spawn().unwrap();
+        let dead_pid = child.id();
+        child.wait().unwrap();
+        write_header_pid(file, dead_pid);
+
+        let reopened = FileBaseLayer::new(file, Some((typ, flags)));
+        assert_eq!(reopened.is_ok(), true);
+
+        std::fs::remove_file(file).unwrap();
+    }
 }
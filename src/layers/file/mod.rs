@@ -4,6 +4,47 @@
 //! file system, serve the requests from the clients, and operate the data in
 //! file(s).
 
+mod bloom;
+mod btree;
 mod file;
+mod sstable;
+mod wal;
 
 pub use file::FileLayer;
+pub(crate) use btree::BTreeLayer;
+pub(crate) use sstable::SSTableLayer;
+pub(crate) use wal::WALLayer;
+
+use crate::layers::traits::Layer;
+use tracing::trace;
+use url::Url;
+
+pub(crate) fn get_file_layer(url: &Url) -> Option<Box<dyn Layer>> {
+    match url.scheme() {
+        "wal" => match WALLayer::open(url) {
+            Ok(layer) => Some(Box::new(layer)),
+            Err(err) => {
+                trace!("failed to open {}: {:?}", &url, err);
+                None
+            }
+        },
+        "btree" => match BTreeLayer::open(url) {
+            Ok(layer) => Some(Box::new(layer)),
+            Err(err) => {
+                trace!("failed to open {}: {:?}", &url, err);
+                None
+            }
+        },
+        "sst" => match SSTableLayer::open(url) {
+            Ok(layer) => Some(Box::new(layer)),
+            Err(err) => {
+                trace!("failed to open {}: {:?}", &url, err);
+                None
+            }
+        },
+        _ => {
+            trace!("failed to get file layer: {:?}", url);
+            None
+        }
+    }
+}
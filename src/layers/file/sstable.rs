@@ -0,0 +1,615 @@
+//! The SSTable-like persistent storage Layer, with a block index and Bloom
+//! filter over its on-disk read path.
+use super::bloom::BloomFilter;
+use super::file::{FileBaseLayer, FileLayer};
+use crate::{Error, Key, Layer, Packer, Pair, Result, Value};
+use std::collections::BTreeMap;
+use tracing::{error, warn};
+use url::Url;
+
+/// the default number of pairs packed into each on-disk block by
+/// [`SSTableLayer::compact`], used unless overridden by the `?block=`
+/// query parameter on the `sst://` URL.
+const BLOCK_ENTRIES: usize = 32;
+
+/// the default target Bloom filter false-positive rate, used unless
+/// overridden by the `?fpr=` query parameter on the `sst://` URL.
+const BLOOM_FPR: f64 = 0.01;
+
+/// The location and key range of one on-disk block.
+#[derive(Debug, Clone)]
+struct BlockMeta {
+    /// the smallest key stored in the block.
+    first_key: Key,
+    /// the offset of the block within the data section.
+    offset: u64,
+    /// the length of the packed block in bytes.
+    len: u64,
+}
+
+/// The SSTable-like persistent storage Layer.
+///
+/// It stores the key-value pairs sorted into fixed-size blocks on top of the
+/// FileBaseLayer, preceded by a small superblock pointer and followed by a
+/// footer holding a Bloom filter and the block index. [`Layer::compact`]
+/// rewrites the whole file into that sorted, block-indexed shape; in between
+/// compactions, new pairs are appended as a small staging log and checked
+/// directly, the same way a memtable sits in front of a real LSM's SSTables.
+///
+/// The data section is laid out as:
+///
+///   - an 8-byte `footer_offset` pointer (0 until the first compaction),
+///   - the compacted blocks, each a run of sorted `Pair::pack()` bytes,
+///   - the footer: a 4-byte length followed by the Bloom filter and block
+///     index written by [`Self::encode_footer`],
+///   - the staging log: every `Pair::pack()` appended by `put`/`del` since
+///     the footer was last written.
+///
+/// [`Layer::compact`] also collapses the staging log's superseded writes
+/// down to each key's latest value, so this layer has no notion of history
+/// to serve from once it runs; [`Layer::get_at`]/[`Layer::history`] are left
+/// on the trait's latest-value-only default. Only
+/// [`super::super::mem::MemLayer`] currently offers real revision history.
+pub struct SSTableLayer {
+    /// the basic file layer and handle the file operations.
+    base: FileBaseLayer,
+    /// the path used to re-open a fresh file on [`Layer::compact`].
+    path: String,
+    /// the block index built from the last compaction, sorted by `first_key`.
+    blocks: Vec<BlockMeta>,
+    /// the Bloom filter covering every key compacted into `blocks`.
+    bloom: BloomFilter,
+    /// pairs appended since the last compaction, newest last.
+    staging: Vec<Pair>,
+    /// the number of pairs packed into each block, from `?block=` or
+    /// [`BLOCK_ENTRIES`].
+    block_entries: usize,
+    /// the target Bloom filter false-positive rate, from `?fpr=` or
+    /// [`BLOOM_FPR`].
+    bloom_fpr: f64,
+}
+
+impl FileLayer for SSTableLayer {
+    /// The type of the SSTable layer.
+    const TYPE: u8 = 0x03;
+}
+
+impl SSTableLayer {
+    /// Read the whole file and rebuild the in-memory superblock pointer,
+    /// block index, Bloom filter and staging log from it.
+    fn load(&mut self) -> Result<()> {
+        let mut data: Vec<u8> = Vec::new();
+        self.base.read_to_end(&mut data)?;
+
+        if data.is_empty() {
+            // brand new file: seed the superblock pointer (footer_offset = 0) so
+            // every later read/append lands after a stable 8-byte prefix.
+            self.base.append(&0u64.to_be_bytes())?;
+            return Ok(());
+        }
+
+        if data.len() < 8 {
+            return Err(Error::InvalidArgument);
+        }
+
+        let footer_offset = u64::from_be_bytes(data[0..8].try_into().unwrap()) as usize;
+
+        if footer_offset == 0 {
+            // never compacted yet, everything after the superblock pointer is staged.
+            self.staging = Pair::unpack_iter(&data[8..]).collect::<Result<Vec<_>>>()?;
+            return Ok(());
+        }
+
+        let footer_len_at = footer_offset;
+        let footer_len = u32::from_be_bytes(
+            data.get(footer_len_at..footer_len_at + 4)
+                .ok_or(Error::InvalidArgument)?
+                .try_into()
+                .unwrap(),
+        ) as usize;
+        let footer_start = footer_len_at + 4;
+        let footer = data
+            .get(footer_start..footer_start + footer_len)
+            .ok_or(Error::InvalidArgument)?;
+
+        let (bloom, blocks) = Self::decode_footer(footer)?;
+        self.bloom = bloom;
+        self.blocks = blocks;
+
+        let staging_start = footer_start + footer_len;
+        self.staging = Pair::unpack_iter(&data[staging_start..]).collect::<Result<Vec<_>>>()?;
+
+        Ok(())
+    }
+
+    /// Read and decode every pair currently stored in a compacted block.
+    fn read_block(&mut self, block: &BlockMeta) -> Vec<Pair> {
+        let mut data = vec![0u8; block.len as usize];
+
+        if let Err(err) = self.base.read_at(&mut data, block.offset as usize) {
+            error!("failed to read sstable block at {}: {:?}", block.offset, err);
+            return Vec::new();
+        }
+
+        Pair::unpack_iter(&data)
+            .filter_map(|item| match item {
+                Ok(pair) => Some(pair),
+                Err(err) => {
+                    warn!(
+                        "failed to decode a pair from sstable block at {}: {:?}",
+                        block.offset, err
+                    );
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Find the index of the last block whose `first_key` is `<= key`, i.e.
+    /// the only block that could contain `key`.
+    fn block_containing(&self, key: &Key) -> Option<usize> {
+        let idx = self.blocks.partition_point(|block| &block.first_key <= key);
+
+        (idx > 0).then_some(idx - 1)
+    }
+
+    /// Encode the Bloom filter and block index into a single footer blob.
+    fn encode_footer(bloom: &BloomFilter, blocks: &[BlockMeta]) -> Vec<u8> {
+        let mut footer: Vec<u8> = Vec::new();
+
+        let bloom_bytes = bloom.to_bytes();
+        footer.extend_from_slice(&(bloom_bytes.len() as u32).to_be_bytes());
+        footer.extend_from_slice(&bloom_bytes);
+
+        footer.extend_from_slice(&(blocks.len() as u32).to_be_bytes());
+        for block in blocks {
+            let key_bytes = block.first_key.pack();
+
+            footer.extend_from_slice(&(key_bytes.len() as u32).to_be_bytes());
+            footer.extend_from_slice(&key_bytes);
+            footer.extend_from_slice(&block.offset.to_be_bytes());
+            footer.extend_from_slice(&block.len.to_be_bytes());
+        }
+
+        footer
+    }
+
+    /// Decode a footer produced by [`Self::encode_footer`].
+    fn decode_footer(data: &[u8]) -> Result<(BloomFilter, Vec<BlockMeta>)> {
+        let mut pos = 0usize;
+
+        let bloom_len = Self::read_u32(data, pos)? as usize;
+        pos += 4;
+        let bloom_bytes = data.get(pos..pos + bloom_len).ok_or(Error::InvalidArgument)?;
+        let bloom = BloomFilter::from_bytes(bloom_bytes);
+        pos += bloom_len;
+
+        let block_count = Self::read_u32(data, pos)? as usize;
+        pos += 4;
+
+        let mut blocks = Vec::with_capacity(block_count);
+        for _ in 0..block_count {
+            let key_len = Self::read_u32(data, pos)? as usize;
+            pos += 4;
+
+            let key_bytes = data.get(pos..pos + key_len).ok_or(Error::InvalidArgument)?;
+            let (first_key, _) = Key::unpack(key_bytes)?;
+            pos += key_len;
+
+            let offset = Self::read_u64(data, pos)?;
+            pos += 8;
+            let len = Self::read_u64(data, pos)?;
+            pos += 8;
+
+            blocks.push(BlockMeta {
+                first_key,
+                offset,
+                len,
+            });
+        }
+
+        Ok((bloom, blocks))
+    }
+
+    fn read_u32(data: &[u8], pos: usize) -> Result<u32> {
+        let bytes: [u8; 4] = data
+            .get(pos..pos + 4)
+            .ok_or(Error::InvalidArgument)?
+            .try_into()
+            .unwrap();
+
+        Ok(u32::from_be_bytes(bytes))
+    }
+
+    fn read_u64(data: &[u8], pos: usize) -> Result<u64> {
+        let bytes: [u8; 8] = data
+            .get(pos..pos + 8)
+            .ok_or(Error::InvalidArgument)?
+            .try_into()
+            .unwrap();
+
+        Ok(u64::from_be_bytes(bytes))
+    }
+}
+
+impl Layer for SSTableLayer {
+    /// Open Layer by the passed URL.
+    fn open(url: &Url) -> Result<Self> {
+        let domain: String = url.domain().unwrap_or_default().to_string();
+        let path: String = format!("{}{}", domain, url.path());
+        let base = FileBaseLayer::new(&path, Some((Self::TYPE, 0)))?;
+
+        let block_entries = url
+            .query_pairs()
+            .find(|(key, _)| key == "block")
+            .and_then(|(_, value)| value.parse().ok())
+            .unwrap_or(BLOCK_ENTRIES);
+        let bloom_fpr = url
+            .query_pairs()
+            .find(|(key, _)| key == "fpr")
+            .and_then(|(_, value)| value.parse().ok())
+            .unwrap_or(BLOOM_FPR);
+
+        let mut layer = Self {
+            base,
+            path,
+            blocks: Vec::new(),
+            bloom: BloomFilter::new(0),
+            staging: Vec::new(),
+            block_entries,
+            bloom_fpr,
+        };
+        layer.load()?;
+
+        Ok(layer)
+    }
+
+    // ======== the general methods ========
+    /// Get the value of the specified key, return None if the key does not exist.
+    /// Note that the value may return if marked as deleted.
+    fn get(&mut self, key: &Key) -> Option<Value> {
+        // the staging log holds the most recent writes, check it first.
+        if let Some(pair) = self.staging.iter().rev().find(|pair| pair.key == *key) {
+            return Some(pair.value.clone());
+        }
+
+        if !self.bloom.might_contain(key) {
+            return None;
+        }
+
+        let idx = self.block_containing(key)?;
+        let block = self.blocks[idx].clone();
+        let pairs = self.read_block(&block);
+
+        pairs
+            .into_iter()
+            .find(|pair| pair.key == *key)
+            .map(|pair| pair.value)
+    }
+
+    /// Set the value of the specified key, which may overwrite and return the old value
+    /// without any warning.
+    fn put(&mut self, key: &Key, value: Value) -> Option<Value> {
+        let pair = Pair::new(key.clone(), value);
+        let data = pair.pack();
+
+        if let Err(err) = self.base.append(&data) {
+            error!("put data got error: {:?}", err);
+        }
+
+        self.staging.push(pair);
+
+        None
+    }
+
+    /// Delete the value of the specified key, which may not actually delete the value
+    /// but mark it as deleted.
+    fn del(&mut self, key: &Key) {
+        let _ = self.put(key, Value::DELETED);
+    }
+
+    // ======== the iteration methods ========
+    /// Iterate over the key-value pairs in the layer which the order is not guaranteed.
+    fn iter(&mut self) -> Box<dyn Iterator<Item = (Key, Value)> + '_> {
+        let pairs = self.merged_pairs();
+
+        Box::new(pairs.into_iter())
+    }
+
+    /// Iterate over the key-value pairs with the ascending order of the key, pass the optional
+    /// based key to start the iteration.
+    fn forward<'a>(
+        &'a mut self,
+        base: Option<&'a Key>,
+    ) -> Box<dyn Iterator<Item = (Key, Value)> + '_> {
+        let mut pairs = self.merged_pairs();
+        pairs.retain(|(key, _)| match base {
+            Some(base) => key >= base,
+            None => true,
+        });
+        pairs.sort_by(|a, b| a.0.cmp(&b.0));
+
+        Box::new(pairs.into_iter())
+    }
+
+    /// Iterate over the key-value pairs with the descending order of the key, pass the optional
+    /// based key to start the iteration.
+    fn backward<'a>(
+        &'a mut self,
+        base: Option<&'a Key>,
+    ) -> Box<dyn Iterator<Item = (Key, Value)> + '_> {
+        let mut pairs = self.merged_pairs();
+        pairs.retain(|(key, _)| match base {
+            Some(base) => key <= base,
+            None => true,
+        });
+        pairs.sort_by(|a, b| b.0.cmp(&a.0));
+
+        Box::new(pairs.into_iter())
+    }
+
+    // ======== the authenticated methods ========
+    /// Remove the existing data and files. The layer may not be initialized until any
+    /// general method is called.
+    fn unlink(&mut self) {
+        self.base.unlink();
+
+        self.blocks.clear();
+        self.bloom = BloomFilter::new(0);
+        self.staging.clear();
+    }
+
+    /// Merge the compacted blocks and the staging log, drop every key whose
+    /// latest value is a tombstone, and rewrite the file as a fresh, sorted
+    /// run of blocks with a new block index and Bloom filter.
+    fn compact(&mut self) {
+        let mut live: BTreeMap<Key, Value> = BTreeMap::new();
+        for (key, value) in self.merged_pairs() {
+            live.insert(key, value);
+        }
+        live.retain(|_, value| *value != Value::DELETED);
+
+        let entries: Vec<(Key, Value)> = live.into_iter().collect();
+        let mut bloom = BloomFilter::with_fpr(entries.len(), self.bloom_fpr);
+        let mut blocks: Vec<BlockMeta> = Vec::new();
+        let mut body: Vec<u8> = Vec::new();
+
+        for chunk in entries.chunks(self.block_entries) {
+            let first_key = chunk[0].0.clone();
+            let offset = 8 + body.len() as u64;
+            let mut block_bytes: Vec<u8> = Vec::new();
+
+            for (key, value) in chunk {
+                bloom.insert(key);
+                block_bytes.extend_from_slice(&Pair::new(key.clone(), value.clone()).pack());
+            }
+
+            blocks.push(BlockMeta {
+                first_key,
+                offset,
+                len: block_bytes.len() as u64,
+            });
+            body.extend_from_slice(&block_bytes);
+        }
+
+        let footer = Self::encode_footer(&bloom, &blocks);
+        let footer_offset = 8 + body.len() as u64;
+
+        let mut file: Vec<u8> = Vec::new();
+        file.extend_from_slice(&footer_offset.to_be_bytes());
+        file.extend_from_slice(&body);
+        file.extend_from_slice(&(footer.len() as u32).to_be_bytes());
+        file.extend_from_slice(&footer);
+
+        self.base.unlink();
+        match FileBaseLayer::new(&self.path, Some((Self::TYPE, 0))) {
+            Ok(base) => self.base = base,
+            Err(err) => {
+                error!("failed to reopen sstable at {}: {:?}", self.path, err);
+                return;
+            }
+        }
+
+        if let Err(err) = self.base.append(&file) {
+            error!("failed to write compacted sstable: {:?}", err);
+            return;
+        }
+
+        self.blocks = blocks;
+        self.bloom = bloom;
+        self.staging.clear();
+    }
+
+    // ======== the interchange methods ========
+    fn archive_meta(&self) -> (u8, u16) {
+        (self.base.typ(), self.base.flags())
+    }
+}
+
+impl SSTableLayer {
+    /// Merge every compacted block with the staging log into one `(Key, Value)`
+    /// list, keeping the newest value per key.
+    fn merged_pairs(&mut self) -> Vec<(Key, Value)> {
+        let mut merged: BTreeMap<Key, Value> = BTreeMap::new();
+
+        let blocks = self.blocks.clone();
+        for block in &blocks {
+            for pair in self.read_block(block) {
+                merged.insert(pair.key, pair.value);
+            }
+        }
+
+        for pair in &self.staging {
+            merged.insert(pair.key.clone(), pair.value.clone());
+        }
+
+        merged.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestContext {
+        path: String,
+    }
+
+    impl TestContext {
+        fn new(path: &str) -> Self {
+            Self {
+                path: path.to_string(),
+            }
+        }
+    }
+
+    impl Drop for TestContext {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+
+    #[test]
+    fn test_sstable_put_and_get() {
+        let file = "test_sstable_put_and_get";
+        let _ctx = TestContext::new(file);
+        let url = Url::parse(&format!("sst://{}", file)).unwrap();
+
+        let mut layer = SSTableLayer::open(&url).unwrap();
+        let key: Key = "key".into();
+        let value: Value = "value".into();
+
+        layer.put(&key, value.clone());
+        assert_eq!(layer.get(&key), Some(value));
+    }
+
+    #[test]
+    fn test_sstable_get_missing_key_is_none() {
+        let file = "test_sstable_get_missing_key_is_none";
+        let _ctx = TestContext::new(file);
+        let url = Url::parse(&format!("sst://{}", file)).unwrap();
+
+        let mut layer = SSTableLayer::open(&url).unwrap();
+        assert_eq!(layer.get(&"missing".into()), None);
+    }
+
+    #[test]
+    fn test_sstable_compact_reads_through_block_index_and_bloom() {
+        let file = "test_sstable_compact_reads_through_block_index_and_bloom";
+        let _ctx = TestContext::new(file);
+        let url = Url::parse(&format!("sst://{}", file)).unwrap();
+
+        let mut layer = SSTableLayer::open(&url).unwrap();
+        for index in 0..(BLOCK_ENTRIES * 3) {
+            let key: Key = index.into();
+            layer.put(&key, "v".into());
+        }
+
+        layer.compact();
+        assert_eq!(layer.blocks.len(), 3);
+
+        for index in 0..(BLOCK_ENTRIES * 3) {
+            let key: Key = index.into();
+            assert_eq!(layer.get(&key), Some("v".into()));
+        }
+
+        assert_eq!(layer.get(&"missing".into()), None);
+    }
+
+    #[test]
+    fn test_sstable_default_block_entries_and_fpr() {
+        let file = "test_sstable_default_block_entries_and_fpr";
+        let _ctx = TestContext::new(file);
+        let url = Url::parse(&format!("sst://{}", file)).unwrap();
+
+        let layer = SSTableLayer::open(&url).unwrap();
+        assert_eq!(layer.block_entries, BLOCK_ENTRIES);
+        assert_eq!(layer.bloom_fpr, BLOOM_FPR);
+    }
+
+    #[test]
+    fn test_sstable_block_entries_and_fpr_from_query() {
+        let file = "test_sstable_block_entries_and_fpr_from_query";
+        let _ctx = TestContext::new(file);
+        let url = Url::parse(&format!("sst://{}?block=4&fpr=0.001", file)).unwrap();
+
+        let mut layer = SSTableLayer::open(&url).unwrap();
+        assert_eq!(layer.block_entries, 4);
+        assert_eq!(layer.bloom_fpr, 0.001);
+
+        for index in 0..12 {
+            let key: Key = index.into();
+            layer.put(&key, "v".into());
+        }
+        layer.compact();
+
+        assert_eq!(layer.blocks.len(), 3);
+        for index in 0..12 {
+            let key: Key = index.into();
+            assert_eq!(layer.get(&key), Some("v".into()));
+        }
+    }
+
+    #[test]
+    fn test_sstable_compact_drops_tombstones() {
+        let file = "test_sstable_compact_drops_tombstones";
+        let _ctx = TestContext::new(file);
+        let url = Url::parse(&format!("sst://{}", file)).unwrap();
+
+        let mut layer = SSTableLayer::open(&url).unwrap();
+        let key: Key = "key".into();
+
+        layer.put(&key, "value".into());
+        layer.del(&key);
+        layer.compact();
+
+        assert_eq!(layer.get(&key), None);
+    }
+
+    #[test]
+    fn test_sstable_survives_reopen_after_compact() {
+        let file = "test_sstable_survives_reopen_after_compact";
+        let _ctx = TestContext::new(file);
+        let url = Url::parse(&format!("sst://{}", file)).unwrap();
+
+        let mut layer = SSTableLayer::open(&url).unwrap();
+        for index in 0..8 {
+            let key: Key = index.into();
+            layer.put(&key, "v".into());
+        }
+        layer.compact();
+        drop(layer);
+
+        let mut reopened = SSTableLayer::open(&url).unwrap();
+        for index in 0..8 {
+            let key: Key = index.into();
+            assert_eq!(reopened.get(&key), Some("v".into()));
+        }
+    }
+
+    #[test]
+    fn test_sstable_forward_and_backward_after_compact() {
+        let file = "test_sstable_forward_and_backward_after_compact";
+        let _ctx = TestContext::new(file);
+        let url = Url::parse(&format!("sst://{}", file)).unwrap();
+
+        let mut layer = SSTableLayer::open(&url).unwrap();
+        for index in 0..8 {
+            let key: Key = index.into();
+            layer.put(&key, "v".into());
+        }
+        layer.compact();
+
+        // another write lands in the staging log, on top of the compacted blocks.
+        let key: Key = 8usize.into();
+        layer.put(&key, "v".into());
+
+        let forward: Vec<Key> = layer.forward(None).map(|(key, _)| key).collect();
+        assert_eq!(forward.len(), 9);
+        assert!(forward.windows(2).all(|w| w[0] < w[1]));
+
+        let backward: Vec<Key> = layer.backward(None).map(|(key, _)| key).collect();
+        assert_eq!(backward.len(), 9);
+        assert!(backward.windows(2).all(|w| w[0] > w[1]));
+    }
+}
@@ -1,20 +1,43 @@
 //! The write-ahead log (WAL) persistence Layer.
 use super::file::{FileBaseLayer, FileLayer};
-use crate::{Key, Layer, Packer, Pair, Result, Value};
-use tracing::error;
+use crate::{CompactPacker, Key, Layer, Packer, Pair, Result, Value};
+use tracing::{error, warn};
 use url::Url;
 
+/// The on-disk encoding a [`WALLayer`] uses to serialize its [`Pair`]s.
+///
+/// `Fixed` is the default: each pair is written with [`Packer`], a tag byte
+/// per key and value followed by its body. `Compact` instead uses the
+/// CBOR-style [`CompactPacker`] encoding, which is smaller for string keys and
+/// `RAW` values. Select it per-layer with the `?pack=compact` query parameter
+/// on the `wal://` URL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum PackMode {
+    #[default]
+    Fixed,
+    Compact,
+}
+
 /// The write-ahead log (WAL) persistence Layer.
 ///
 /// It store the key-value pairs in a log file based on the FileBaseLayer, which
 /// provides the Layer like operations and store into the data section on the
 /// FileBaseLayer.
+///
+/// Every record written to the log is, in principle, a revision of its key,
+/// but this layer does not yet index them that way: it leaves
+/// [`Layer::get_at`]/[`Layer::history`] on the trait's latest-value-only
+/// default rather than replaying the log to reconstruct past versions. Only
+/// [`super::super::mem::MemLayer`] currently offers real revision history.
 pub struct WALLayer {
     /// The basic file layer and handle the file operations.
     base: FileBaseLayer,
 
     /// the internal bit to check the layer is initialized.
     initialized: bool,
+
+    /// the on-disk encoding used to serialize pairs.
+    mode: PackMode,
 }
 
 impl FileLayer for WALLayer {
@@ -22,6 +45,31 @@ impl FileLayer for WALLayer {
     const TYPE: u8 = 0x01;
 }
 
+impl WALLayer {
+    /// Serialize a pair using the layer's selected [`PackMode`].
+    fn pack_pair(&self, pair: &Pair) -> Vec<u8> {
+        match self.mode {
+            PackMode::Fixed => pair.pack(),
+            PackMode::Compact => pair.pack_compact(),
+        }
+    }
+
+    /// Decode as many [`PackMode::Fixed`] pairs out of `data` as possible,
+    /// warning about and then stopping at the first corrupt one rather than
+    /// silently dropping it (and everything unpacked after it).
+    fn unpack_fixed_pairs(data: &[u8]) -> Vec<Pair> {
+        Pair::unpack_iter(data)
+            .filter_map(|item| match item {
+                Ok(pair) => Some(pair),
+                Err(err) => {
+                    warn!("failed to decode a wal pair: {:?}", err);
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
 impl Layer for WALLayer {
     /// Open Layer by the passed URL.
     fn open(url: &Url) -> Result<Self> {
@@ -29,9 +77,19 @@ impl Layer for WALLayer {
         let path: String = format!("{}{}", domain, url.path());
         let base = FileBaseLayer::new(&path, Some((Self::TYPE, 0)))?;
 
+        let mode = match url
+            .query_pairs()
+            .find(|(key, _)| key == "pack")
+            .map(|(_, value)| value.into_owned())
+        {
+            Some(value) if value == "compact" => PackMode::Compact,
+            _ => PackMode::Fixed,
+        };
+
         Ok(Self {
             base,
             initialized: true,
+            mode,
         })
     }
 
@@ -55,8 +113,9 @@ impl Layer for WALLayer {
     /// without any warning.
     fn put(&mut self, key: &Key, value: Value) -> Option<Value> {
         let pair = Pair::new(key.clone(), value);
+        let data = self.pack_pair(&pair);
 
-        if let Err(err) = self.base.append(&pair.pack()) {
+        if let Err(err) = self.base.append(&data) {
             error!("put data got error: {:?}", err);
         }
 
@@ -77,7 +136,10 @@ impl Layer for WALLayer {
         let mut data: Vec<u8> = Vec::new();
         let _ = self.base.read_to_end(&mut data);
 
-        let pairs: Vec<Pair> = Pair::unpack_iter(&data).collect();
+        let pairs: Vec<Pair> = match self.mode {
+            PackMode::Fixed => Self::unpack_fixed_pairs(&data),
+            PackMode::Compact => Pair::unpack_compact_iter(&data).collect(),
+        };
 
         Box::new(pairs.into_iter().map(move |pair| (pair.key, pair.value)))
     }
@@ -91,12 +153,14 @@ impl Layer for WALLayer {
         let mut data: Vec<u8> = Vec::new();
         let _ = self.base.read_to_end(&mut data);
 
-        let mut pairs: Vec<Pair> = Pair::unpack_iter(&data)
-            .filter(move |x| match base {
-                Some(base) => x.key >= *base,
-                None => true,
-            })
-            .collect();
+        let mut pairs: Vec<Pair> = match self.mode {
+            PackMode::Fixed => Self::unpack_fixed_pairs(&data),
+            PackMode::Compact => Pair::unpack_compact_iter(&data).collect(),
+        };
+        pairs.retain(|x| match base {
+            Some(base) => x.key >= *base,
+            None => true,
+        });
 
         // sort by the key order
         pairs.sort_by(|a, b| a.key.cmp(&b.key));
@@ -113,12 +177,14 @@ impl Layer for WALLayer {
         let mut data: Vec<u8> = Vec::new();
         let _ = self.base.read_to_end(&mut data);
 
-        let mut pairs: Vec<Pair> = Pair::unpack_iter(&data)
-            .filter(move |x| match base {
-                Some(base) => x.key <= *base,
-                None => true,
-            })
-            .collect();
+        let mut pairs: Vec<Pair> = match self.mode {
+            PackMode::Fixed => Self::unpack_fixed_pairs(&data),
+            PackMode::Compact => Pair::unpack_compact_iter(&data).collect(),
+        };
+        pairs.retain(|x| match base {
+            Some(base) => x.key <= *base,
+            None => true,
+        });
 
         // sort by the key order
         pairs.sort_by(|a, b| b.key.cmp(&a.key));
@@ -139,4 +205,93 @@ impl Layer for WALLayer {
     /// Remove all the data marked as deleted, reorganize the data and file, and make
     /// the layer compact.
     fn compact(&mut self) {}
+
+    // ======== the interchange methods ========
+    fn archive_meta(&self) -> (u8, u16) {
+        (self.base.typ(), self.base.flags())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestContext {
+        path: String,
+    }
+
+    impl TestContext {
+        fn new(path: &str) -> Self {
+            Self {
+                path: path.to_string(),
+            }
+        }
+    }
+
+    impl Drop for TestContext {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+
+    #[test]
+    fn test_wal_default_pack_mode_is_fixed() {
+        let file = "test_wal_default_pack_mode_is_fixed";
+        let _ctx = TestContext::new(file);
+        let url = Url::parse(&format!("wal://{}", file)).unwrap();
+
+        let layer = WALLayer::open(&url).unwrap();
+        assert_eq!(layer.mode, PackMode::Fixed);
+    }
+
+    #[test]
+    fn test_wal_compact_pack_mode_from_query() {
+        let file = "test_wal_compact_pack_mode_from_query";
+        let _ctx = TestContext::new(file);
+        let url = Url::parse(&format!("wal://{}?pack=compact", file)).unwrap();
+
+        let layer = WALLayer::open(&url).unwrap();
+        assert_eq!(layer.mode, PackMode::Compact);
+    }
+
+    #[test]
+    fn test_wal_compact_mode_put_and_get() {
+        let file = "test_wal_compact_mode_put_and_get";
+        let _ctx = TestContext::new(file);
+        let url = Url::parse(&format!("wal://{}?pack=compact", file)).unwrap();
+
+        let mut layer = WALLayer::open(&url).unwrap();
+        let key: Key = "key".into();
+        let value: Value = "value".into();
+
+        layer.put(&key, value.clone());
+        assert_eq!(layer.get(&key), Some(value));
+    }
+
+    #[test]
+    fn test_wal_compact_mode_shrinks_log() {
+        let fixed_file = "test_wal_compact_mode_shrinks_log_fixed";
+        let compact_file = "test_wal_compact_mode_shrinks_log_compact";
+        let _fixed_ctx = TestContext::new(fixed_file);
+        let _compact_ctx = TestContext::new(compact_file);
+
+        let fixed_url = Url::parse(&format!("wal://{}", fixed_file)).unwrap();
+        let compact_url = Url::parse(&format!("wal://{}?pack=compact", compact_file)).unwrap();
+
+        let mut fixed = WALLayer::open(&fixed_url).unwrap();
+        let mut compact = WALLayer::open(&compact_url).unwrap();
+
+        for index in 0..16 {
+            let key: Key = index.into();
+            let value: Value = "v".into();
+
+            fixed.put(&key, value.clone());
+            compact.put(&key, value);
+        }
+
+        let fixed_size = std::fs::metadata(fixed_file).unwrap().len();
+        let compact_size = std::fs::metadata(compact_file).unwrap().len();
+
+        assert!(compact_size < fixed_size);
+    }
 }
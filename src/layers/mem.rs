@@ -14,6 +14,15 @@ pub struct MemLayer {
 
     /// the pool to hold the deleted keys.
     _del: BTreeSet<Key>,
+
+    /// every `put`/`del` ever applied to a key, oldest first, with `None`
+    /// marking a delete; never pruned by [`MemLayer::compact`] since old
+    /// revisions must stay reachable through [`Layer::get_at`]/[`Layer::history`].
+    _history: HashMap<Key, Vec<(u64, Option<Value>)>>,
+
+    /// the revision stamped on the most recent `put`/`del`, monotonically
+    /// increasing from `0`.
+    _revision: u64,
 }
 
 impl Layer for MemLayer {
@@ -37,6 +46,12 @@ impl Layer for MemLayer {
     /// Set the value of the specified key, which may overwrite and return the old value
     /// without any warning.
     fn put(&mut self, key: &Key, value: Value) -> Option<Value> {
+        self._revision += 1;
+        self._history
+            .entry(key.clone())
+            .or_default()
+            .push((self._revision, Some(value.clone())));
+
         self._del.insert(key.clone());
         self._mem.insert(key.clone(), value)
     }
@@ -44,6 +59,12 @@ impl Layer for MemLayer {
     /// Delete the value of the specified key, which may not actually delete the value
     /// but mark it as deleted.
     fn del(&mut self, key: &Key) {
+        self._revision += 1;
+        self._history
+            .entry(key.clone())
+            .or_default()
+            .push((self._revision, None));
+
         self._mem.remove(key);
     }
 
@@ -110,4 +131,31 @@ impl Layer for MemLayer {
         self._del.clear();
         self._del = self._mem.keys().cloned().collect();
     }
+
+    // ======== the revision methods ========
+    /// Get the value of the specified key as of `revision`, return `None` if
+    /// the key did not exist yet, or was deleted, at that point in time.
+    fn get_at(&mut self, key: &Key, revision: u64) -> Option<Value> {
+        self._history
+            .get(key)
+            .into_iter()
+            .flatten()
+            .take_while(|(rev, _)| *rev <= revision)
+            .last()
+            .and_then(|(_, value)| value.clone())
+    }
+
+    /// Iterate over every `(revision, value)` recorded for `key`, oldest
+    /// first, with `None` marking a delete (a tombstone) at that revision.
+    fn history(&mut self, key: &Key) -> Box<dyn Iterator<Item = (u64, Option<Value>)> + '_> {
+        Box::new(
+            self._history
+                .get(key)
+                .into_iter()
+                .flatten()
+                .cloned()
+                .collect::<Vec<_>>()
+                .into_iter(),
+        )
+    }
 }
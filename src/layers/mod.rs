@@ -3,11 +3,19 @@
 //! `Layer` is the core of Papyrus. It is the main interface for interacting with
 //! the key-value pairs in Papyrus. It provides few methods and is designed to be
 //! simple to use.
+mod archive;
+mod async_layer;
+mod cbor;
 mod file;
 mod mem;
 mod traits;
+mod transport;
 
+pub(crate) use file::get_file_layer;
 pub(crate) use mem::MemLayer;
-pub use traits::{get_layer, Layer};
+pub(crate) use transport::get_remote_layer;
+pub use traits::{get_layer, BatchOp, Layer};
 
+pub use async_layer::{get_async_layer, AsyncLayer, BlockingLayer};
 pub use file::FileLayer;
+pub use transport::{AsyncClient, Server, SyncClient, TcpClient};
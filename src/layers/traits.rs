@@ -1,9 +1,21 @@
 //! The abstraction of the Layer.
-use crate::layers::{get_file_layer, MemLayer};
+use crate::layers::{archive, cbor};
+use crate::layers::{get_file_layer, get_remote_layer, MemLayer};
 use crate::{Key, Result, Value};
+use std::io::{Read, Write};
 use tracing::{trace, warn};
 use url::Url;
 
+/// A single operation buffered by a transaction block and applied as a unit
+/// through [`Layer::batch`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum BatchOp {
+    /// Set the value of the given key.
+    Put(Key, Value),
+    /// Delete the value of the given key.
+    Del(Key),
+}
+
 /// The abstraction of the Layer.
 ///
 /// `Layer` provides general methods for interacting with the key-value pairs,
@@ -28,6 +40,25 @@ pub trait Layer {
     /// but mark it as deleted.
     fn del(&mut self, key: &Key);
 
+    /// Apply a batch of [`BatchOp`]s as a single unit, e.g. the buffered
+    /// put/del commands inside a `begin ... commit` transaction block.
+    ///
+    /// The default implementation simply replays each op through [`Layer::put`]
+    /// / [`Layer::del`]; layers that can offer a stronger atomicity guarantee
+    /// may override it.
+    fn batch(&mut self, ops: Vec<BatchOp>) {
+        for op in ops {
+            match op {
+                BatchOp::Put(key, value) => {
+                    self.put(&key, value);
+                }
+                BatchOp::Del(key) => {
+                    self.del(&key);
+                }
+            }
+        }
+    }
+
     // ======== the iteration methods ========
     /// Iterate over the key-value pairs in the layer which the order is not guaranteed.
     fn iter(&mut self) -> Box<dyn Iterator<Item = (Key, Value)> + '_>;
@@ -54,6 +85,85 @@ pub trait Layer {
     /// Remove all the data marked as deleted, reorganize the data and file, and make
     /// the layer compact.
     fn compact(&mut self);
+
+    // ======== the revision methods ========
+    /// Get the value of the specified key as of `revision`, return `None` if
+    /// the key did not exist yet at that point in time.
+    ///
+    /// The default implementation has no notion of history and simply falls
+    /// back to the latest value via [`Layer::get`]; a layer that retains old
+    /// versions should override it alongside [`Layer::history`]. Of the
+    /// layers in this crate, only [`MemLayer`] does today -- the other layers
+    /// (`wal://`, `btree://`, `sst://`, `tcp://`) keep no per-revision index
+    /// and so are left on this default.
+    fn get_at(&mut self, key: &Key, revision: u64) -> Option<Value> {
+        let _ = revision;
+
+        self.get(key)
+    }
+
+    /// Iterate over every `(revision, value)` recorded for `key`, oldest
+    /// first, with `None` marking a delete (a tombstone) at that revision.
+    ///
+    /// The default implementation has no notion of history and yields a single
+    /// `(0, self.get(key))` item: the latest value as revision `0`, or `None`
+    /// when the key is absent. See [`Layer::get_at`] for which
+    /// layers override this with real history.
+    fn history(&mut self, key: &Key) -> Box<dyn Iterator<Item = (u64, Option<Value>)> + '_> {
+        Box::new(std::iter::once((0, self.get(key))))
+    }
+
+    // ======== the interchange methods ========
+    /// Export the whole layer's contents as a standard CBOR byte string: a
+    /// top-level array of `[key, value]` arrays, so the data can be inspected
+    /// or migrated with CBOR tooling outside the crate.
+    fn export_cbor(&mut self) -> Vec<u8> {
+        let pairs: Vec<(Key, Value)> = self.iter().collect();
+        cbor::encode_pairs(pairs.into_iter())
+    }
+
+    /// Import a CBOR byte string produced by [`Layer::export_cbor`], replaying
+    /// every pair through [`Layer::put`].
+    fn import_cbor(&mut self, data: &[u8]) -> Result<()> {
+        for (key, value) in cbor::decode_pairs(data)? {
+            self.put(&key, value);
+        }
+
+        Ok(())
+    }
+
+    /// Export the whole layer's contents as a portable ustar tar archive:
+    /// a manifest entry followed by one entry per key-value pair. See
+    /// [`crate::layers::archive`] for the on-disk format.
+    ///
+    /// Unlike [`Layer::export_cbor`], this streams straight to `writer`
+    /// instead of buffering the whole archive. The pairs come from
+    /// [`Layer::iter`], which the file-backed layers collect in memory in full.
+    fn export_archive(&mut self, writer: &mut dyn Write) -> Result<()> {
+        let meta = self.archive_meta();
+
+        archive::encode_entries(self.iter(), meta, writer)
+    }
+
+    /// Import a tar archive produced by [`Layer::export_archive`], replaying
+    /// every pair (including tombstones) through [`Layer::put`].
+    fn import_archive(&mut self, reader: &mut dyn Read) -> Result<()> {
+        let (_, pairs) = archive::decode_entries(reader)?;
+
+        for (key, value) in pairs {
+            self.put(&key, value);
+        }
+
+        Ok(())
+    }
+
+    /// The `(type, flags)` recorded in [`Layer::export_archive`]'s manifest
+    /// entry. Layers with no on-disk file format (e.g. [`MemLayer`]) keep the
+    /// default `(0, 0)`; file-backed layers override it with their
+    /// [`crate::layers::FileLayer`] type and flags.
+    fn archive_meta(&self) -> (u8, u16) {
+        (0, 0)
+    }
 }
 
 /// Get the Layer via passed URL.
@@ -70,6 +180,9 @@ pub fn get_layer(url: &str) -> Option<Box<dyn Layer>> {
                 }
             },
             "wal" => get_file_layer(&url),
+            "btree" => get_file_layer(&url),
+            "sst" => get_file_layer(&url),
+            "tcp" => get_remote_layer(&url),
             _ => {
                 warn!("cannot find scheme {} for layer", url.scheme());
                 None
@@ -156,6 +269,54 @@ mod tests {
                     assert_eq!(layer.get(&key), None);
                 }
 
+                #[test]
+                fn [<test_layer_export_import_cbor_round_trip_on_ $scheme>]() {
+                    let bool_key: Key = Key::BOOL(true);
+                    let int_key: Key = Key::INT(-7);
+                    let str_key: Key = "key".into();
+                    let value: Value = "value".into();
+
+                    let mut layer = get_layer($url).unwrap();
+
+                    layer.put(&bool_key, Value::DELETED);
+                    layer.put(&int_key, "seven".into());
+                    layer.put(&str_key, value.clone());
+
+                    let data = layer.export_cbor();
+
+                    let mut other = get_layer($url).unwrap();
+                    other.import_cbor(&data).unwrap();
+
+                    assert_eq!(other.get(&bool_key), Some(Value::DELETED));
+                    assert_eq!(other.get(&int_key), Some("seven".into()));
+                    assert_eq!(other.get(&str_key), Some(value));
+                }
+
+                #[test]
+                fn [<test_layer_export_import_archive_round_trip_on_ $scheme>]() {
+                    let bool_key: Key = Key::BOOL(true);
+                    let int_key: Key = Key::INT(-7);
+                    let str_key: Key = "key".into();
+                    let value: Value = "value".into();
+
+                    let mut layer = get_layer($url).unwrap();
+
+                    layer.put(&bool_key, "deleted soon".into());
+                    layer.del(&bool_key);
+                    layer.put(&int_key, "seven".into());
+                    layer.put(&str_key, value.clone());
+
+                    let mut data = Vec::new();
+                    layer.export_archive(&mut data).unwrap();
+
+                    let mut other = get_layer($url).unwrap();
+                    other.import_archive(&mut data.as_slice()).unwrap();
+
+                    assert_eq!(other.get(&bool_key), Some(Value::DELETED));
+                    assert_eq!(other.get(&int_key), Some("seven".into()));
+                    assert_eq!(other.get(&str_key), Some(value));
+                }
+
                 test_layer_iter!($scheme, $url, 0);
                 test_layer_iter!($scheme, $url, 1);
                 test_layer_iter!($scheme, $url, 2);
@@ -272,4 +433,66 @@ mod tests {
     }
 
     test_layer!(mem, "mem://");
+
+    #[test]
+    fn test_layer_batch_applies_puts_and_dels_as_a_unit() {
+        let mut layer = get_layer("mem://").unwrap();
+
+        layer.put(&"a".into(), "old".into());
+
+        layer.batch(vec![
+            BatchOp::Put("a".into(), "new".into()),
+            BatchOp::Put("b".into(), "value".into()),
+            BatchOp::Del("a".into()),
+        ]);
+
+        assert_eq!(layer.get(&"b".into()), Some("value".into()));
+        assert_eq!(layer.get(&"a".into()), Some(Value::DELETED));
+    }
+
+    #[test]
+    fn test_layer_get_at_reads_the_value_as_of_a_past_revision() {
+        let key: Key = "key".into();
+        let mut layer = get_layer("mem://").unwrap();
+
+        layer.put(&key, "v1".into()); // revision 1
+        layer.put(&key, "v2".into()); // revision 2
+        layer.del(&key); // revision 3
+
+        assert_eq!(layer.get_at(&key, 0), None);
+        assert_eq!(layer.get_at(&key, 1), Some("v1".into()));
+        assert_eq!(layer.get_at(&key, 2), Some("v2".into()));
+        assert_eq!(layer.get_at(&key, 3), None);
+        assert_eq!(layer.get_at(&key, 42), None);
+    }
+
+    #[test]
+    fn test_layer_history_yields_every_revision_including_tombstones() {
+        let key: Key = "key".into();
+        let mut layer = get_layer("mem://").unwrap();
+
+        layer.put(&key, "v1".into());
+        layer.put(&key, "v2".into());
+        layer.del(&key);
+
+        let history: Vec<(u64, Option<Value>)> = layer.history(&key).collect();
+
+        assert_eq!(
+            history,
+            vec![(1, Some("v1".into())), (2, Some("v2".into())), (3, None)]
+        );
+    }
+
+    #[test]
+    fn test_layer_history_survives_compact() {
+        let key: Key = "key".into();
+        let mut layer = get_layer("mem://").unwrap();
+
+        layer.put(&key, "v1".into());
+        layer.del(&key);
+        layer.compact();
+
+        assert_eq!(layer.history(&key).count(), 2);
+        assert_eq!(layer.get_at(&key, 1), Some("v1".into()));
+    }
 }
@@ -0,0 +1,254 @@
+//! Blocking and fire-and-forget clients for talking to a [`super::Server`].
+use super::server::{read_frame, write_frame, Op, STATUS_ERR, STATUS_NONE, STATUS_OK};
+use crate::{Error, Key, Layer, Packer, Pair, Result, Value};
+use std::io::Write;
+use std::net::TcpStream;
+use std::time::Duration;
+use tracing::warn;
+use url::Url;
+
+/// the number of connection attempts a [`SyncClient`] makes before giving up
+/// on a transient connection failure.
+const RETRY_ATTEMPTS: usize = 3;
+/// the delay between retries.
+const RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// A client whose methods block until the server acknowledges the request,
+/// retrying on transient (connection) failures.
+pub trait SyncClient {
+    /// Get the value of the specified key, return None if the key does not exist.
+    fn get(&mut self, key: &Key) -> Option<Value>;
+
+    /// Set the value of the specified key, which may overwrite and return the old value.
+    fn put(&mut self, key: &Key, value: Value) -> Option<Value>;
+
+    /// Delete the value of the specified key.
+    fn del(&mut self, key: &Key);
+
+    /// Iterate over the key-value pairs with the ascending order of the key.
+    fn forward(&mut self, base: Option<&Key>) -> Vec<(Key, Value)>;
+
+    /// Iterate over the key-value pairs with the descending order of the key.
+    fn backward(&mut self, base: Option<&Key>) -> Vec<(Key, Value)>;
+}
+
+/// A client whose methods fire the request and return immediately, without
+/// waiting for the server's acknowledgement.
+pub trait AsyncClient {
+    /// Set the value of the specified key without waiting for the server to apply it.
+    fn put(&mut self, key: &Key, value: Value);
+
+    /// Delete the value of the specified key without waiting for the server to apply it.
+    fn del(&mut self, key: &Key);
+}
+
+/// The TCP-backed implementation shared by [`SyncClient`] and [`AsyncClient`].
+///
+/// It speaks the same length-prefixed request/response protocol as
+/// [`super::Server`]: `[op: u8][Pair::pack() or Key::pack()]` in, `[status:
+/// u8][Value::pack()]` out. `forward`/`backward` are streamed instead: the
+/// server writes one pair per frame, and [`Self::stream_pairs`] keeps reading
+/// frames until the terminating `[STATUS_NONE]` frame arrives.
+#[derive(Debug, Clone)]
+pub struct TcpClient {
+    addr: String,
+}
+
+impl TcpClient {
+    /// Create a client for the server listening at the given address. No
+    /// connection is made here; each request opens its own.
+    pub fn new(addr: &str) -> Self {
+        Self {
+            addr: addr.to_string(),
+        }
+    }
+
+    /// Connect to the server, retrying on transient failures.
+    fn connect(&self) -> Result<TcpStream> {
+        let mut last_err = None;
+
+        for attempt in 0..RETRY_ATTEMPTS {
+            match TcpStream::connect(&self.addr) {
+                Ok(stream) => return Ok(stream),
+                Err(err) => {
+                    warn!(
+                        "failed to connect to {} (attempt {}/{}): {:?}",
+                        self.addr,
+                        attempt + 1,
+                        RETRY_ATTEMPTS,
+                        err
+                    );
+
+                    last_err = Some(err);
+                    std::thread::sleep(RETRY_DELAY);
+                }
+            }
+        }
+
+        Err(last_err.expect("at least one connection attempt").into())
+    }
+
+    /// Send a request and block until the response frame arrives.
+    fn request(&self, op: Op, payload: &[u8]) -> Result<Vec<u8>> {
+        let mut stream = self.connect()?;
+
+        let mut frame = vec![op as u8];
+        frame.extend_from_slice(payload);
+
+        write_frame(&mut stream, &frame)?;
+        let response = read_frame(&mut stream)?;
+
+        Ok(response)
+    }
+
+    /// Send an `Op::Forward`/`Op::Backward` request and collect the streamed
+    /// pairs, reading frames off the connection until the server's terminating
+    /// `[STATUS_NONE]` frame arrives.
+    fn stream_pairs(&self, op: Op, payload: &[u8]) -> Vec<(Key, Value)> {
+        let mut stream = match self.connect() {
+            Ok(stream) => stream,
+            Err(_) => return Vec::new(),
+        };
+
+        let mut frame = vec![op as u8];
+        frame.extend_from_slice(payload);
+
+        if write_frame(&mut stream, &frame).is_err() {
+            return Vec::new();
+        }
+
+        let mut pairs = Vec::new();
+        loop {
+            let response = match read_frame(&mut stream) {
+                Ok(response) => response,
+                Err(_) => break,
+            };
+
+            match response.first() {
+                Some(&STATUS_OK) => match Pair::unpack(&response[1..]) {
+                    Ok((pair, _)) => pairs.push((pair.key, pair.value)),
+                    Err(_) => break,
+                },
+                _ => break,
+            }
+        }
+
+        pairs
+    }
+
+    /// Send a request without waiting for the response, on a detached connection.
+    fn fire(&self, op: Op, payload: &[u8]) {
+        let addr = self.addr.clone();
+        let mut frame = vec![op as u8];
+        frame.extend_from_slice(payload);
+
+        // don't block the caller on the server's acknowledgement
+        std::thread::spawn(move || {
+            if let Ok(mut stream) = TcpStream::connect(&addr) {
+                let _ = write_frame(&mut stream, &frame);
+                let _ = stream.flush();
+            }
+        });
+    }
+
+    fn decode_value(response: &[u8]) -> Option<Value> {
+        match response.first() {
+            Some(&STATUS_OK) => Value::unpack(&response[1..]).ok().map(|(value, _)| value),
+            Some(&STATUS_NONE) | Some(&STATUS_ERR) | None => None,
+            Some(_) => None,
+        }
+    }
+}
+
+impl SyncClient for TcpClient {
+    fn get(&mut self, key: &Key) -> Option<Value> {
+        let response = self.request(Op::Get, &key.pack()).ok()?;
+        Self::decode_value(&response)
+    }
+
+    fn put(&mut self, key: &Key, value: Value) -> Option<Value> {
+        let pair = Pair::new(key.clone(), value);
+        let response = self.request(Op::Put, &pair.pack()).ok()?;
+
+        Self::decode_value(&response)
+    }
+
+    fn del(&mut self, key: &Key) {
+        let _ = self.request(Op::Del, &key.pack());
+    }
+
+    fn forward(&mut self, base: Option<&Key>) -> Vec<(Key, Value)> {
+        let payload = base.map(|key| key.pack()).unwrap_or_default();
+        self.stream_pairs(Op::Forward, &payload)
+    }
+
+    fn backward(&mut self, base: Option<&Key>) -> Vec<(Key, Value)> {
+        let payload = base.map(|key| key.pack()).unwrap_or_default();
+        self.stream_pairs(Op::Backward, &payload)
+    }
+}
+
+impl AsyncClient for TcpClient {
+    fn put(&mut self, key: &Key, value: Value) {
+        let pair = Pair::new(key.clone(), value);
+        self.fire(Op::Put, &pair.pack());
+    }
+
+    fn del(&mut self, key: &Key) {
+        self.fire(Op::Del, &key.pack());
+    }
+}
+
+/// The `Layer` facing a remote [`super::Server`] over `tcp://host:port/...`.
+///
+/// It delegates `get`/`put`/`del` and iteration to a blocking [`TcpClient`],
+/// so the embeddable store can be used against a remote process; `unlink` and
+/// `compact` do nothing here.
+pub struct RemoteLayer {
+    client: TcpClient,
+}
+
+impl Layer for RemoteLayer {
+    fn open(url: &Url) -> Result<Self> {
+        let host = url.host_str().ok_or(Error::InvalidArgument)?;
+        let port = url.port().ok_or(Error::InvalidArgument)?;
+
+        Ok(Self {
+            client: TcpClient::new(&format!("{}:{}", host, port)),
+        })
+    }
+
+    fn get(&mut self, key: &Key) -> Option<Value> {
+        SyncClient::get(&mut self.client, key)
+    }
+
+    fn put(&mut self, key: &Key, value: Value) -> Option<Value> {
+        SyncClient::put(&mut self.client, key, value)
+    }
+
+    fn del(&mut self, key: &Key) {
+        SyncClient::del(&mut self.client, key)
+    }
+
+    fn iter(&mut self) -> Box<dyn Iterator<Item = (Key, Value)> + '_> {
+        Box::new(SyncClient::forward(&mut self.client, None).into_iter())
+    }
+
+    fn forward<'a>(
+        &'a mut self,
+        base: Option<&'a Key>,
+    ) -> Box<dyn Iterator<Item = (Key, Value)> + '_> {
+        Box::new(SyncClient::forward(&mut self.client, base).into_iter())
+    }
+
+    fn backward<'a>(
+        &'a mut self,
+        base: Option<&'a Key>,
+    ) -> Box<dyn Iterator<Item = (Key, Value)> + '_> {
+        Box::new(SyncClient::backward(&mut self.client, base).into_iter())
+    }
+
+    fn unlink(&mut self) {}
+
+    fn compact(&mut self) {}
+}
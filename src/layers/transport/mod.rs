@@ -0,0 +1,22 @@
+//! The network transport for serving and consuming a [`Layer`](super::Layer) over TCP.
+mod client;
+mod server;
+
+pub use client::{AsyncClient, SyncClient, TcpClient};
+pub use server::Server;
+
+use crate::layers::traits::Layer;
+use client::RemoteLayer;
+use tracing::trace;
+use url::Url;
+
+/// Get a remote layer that talks to a [`Server`] over `tcp://host:port`.
+pub(crate) fn get_remote_layer(url: &Url) -> Option<Box<dyn Layer>> {
+    match RemoteLayer::open(url) {
+        Ok(layer) => Some(Box::new(layer)),
+        Err(err) => {
+            trace!("failed to open {}: {:?}", &url, err);
+            None
+        }
+    }
+}
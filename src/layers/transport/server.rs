@@ -0,0 +1,187 @@
+//! The TCP server that exposes any [`Layer`] to remote clients.
+use crate::layers::traits::Layer;
+use crate::{Key, Packer, Pair, Value};
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use tracing::{error, trace, warn};
+
+/// the request opcodes understood by [`Server`], shared with the clients in
+/// the sibling `client` module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Op {
+    Get = 0,
+    Put = 1,
+    Del = 2,
+    Forward = 3,
+    Backward = 4,
+}
+
+impl Op {
+    pub(crate) fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(Op::Get),
+            1 => Some(Op::Put),
+            2 => Some(Op::Del),
+            3 => Some(Op::Forward),
+            4 => Some(Op::Backward),
+            _ => None,
+        }
+    }
+}
+
+/// the response status understood by the clients in the sibling `client` module.
+pub(crate) const STATUS_OK: u8 = 0;
+pub(crate) const STATUS_NONE: u8 = 1;
+pub(crate) const STATUS_ERR: u8 = 2;
+
+/// Read a single length-prefixed frame off the stream.
+pub(crate) fn read_frame(stream: &mut TcpStream) -> std::io::Result<Vec<u8>> {
+    let mut len = [0u8; 4];
+    stream.read_exact(&mut len)?;
+
+    let mut data = vec![0u8; u32::from_be_bytes(len) as usize];
+    stream.read_exact(&mut data)?;
+
+    Ok(data)
+}
+
+/// Write a single length-prefixed frame to the stream.
+pub(crate) fn write_frame(stream: &mut TcpStream, data: &[u8]) -> std::io::Result<()> {
+    let len = (data.len() as u32).to_be_bytes();
+
+    stream.write_all(&len)?;
+    stream.write_all(data)?;
+    stream.flush()
+}
+
+/// A TCP server that wraps any [`Layer`] and serves it to remote clients.
+///
+/// It speaks a tiny request/response protocol on top of length-prefixed
+/// frames: the request frame is `[op: u8][Pair::pack() or Key::pack()]`, and
+/// the response frame is `[status: u8][Value::pack()]`, reusing the existing
+/// `Pair`/`Packer` wire format instead of inventing a new one. [`Op::Forward`]
+/// and [`Op::Backward`] are the exception: rather than buffering the whole
+/// iteration into one frame, the server writes one `[STATUS_OK][Pair::pack()]`
+/// frame per pair as it walks the layer, followed by a single `[STATUS_NONE]`
+/// frame once the iteration is exhausted.
+///
+/// It takes the same `Box<dyn Layer>` that [`crate::get_layer`] returns, so
+/// any layer opened by URL can be served without extra plumbing.
+pub struct Server {
+    layer: Box<dyn Layer>,
+}
+
+impl Server {
+    /// Wrap the given layer so it can be served over the network.
+    pub fn new(layer: Box<dyn Layer>) -> Self {
+        Self { layer }
+    }
+
+    /// Serve the wrapped layer on the given address, blocking forever.
+    pub fn serve(mut self, addr: &str) -> crate::Result<()> {
+        let listener = TcpListener::bind(addr)?;
+        trace!("serving layer on {}", addr);
+
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => self.handle(stream),
+                Err(err) => error!("failed to accept connection: {:?}", err),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Handle a single client connection until it disconnects.
+    fn handle(&mut self, mut stream: TcpStream) {
+        loop {
+            let frame = match read_frame(&mut stream) {
+                Ok(frame) => frame,
+                Err(_) => return,
+            };
+
+            if frame.is_empty() {
+                warn!("received an empty request frame");
+                return;
+            }
+
+            let op = match Op::from_byte(frame[0]) {
+                Some(op) => op,
+                None => {
+                    warn!("received an unknown opcode: {}", frame[0]);
+                    return;
+                }
+            };
+
+            let result = match op {
+                Op::Forward | Op::Backward => self.stream_pairs(op, &frame[1..], &mut stream),
+                _ => write_frame(&mut stream, &self.dispatch(op, &frame[1..])),
+            };
+
+            if result.is_err() {
+                return;
+            }
+        }
+    }
+
+    /// Execute the request against the wrapped layer and return the response frame.
+    ///
+    /// Only covers the request/response ops; [`Op::Forward`]/[`Op::Backward`]
+    /// stream their results frame-by-frame instead, via [`Self::stream_pairs`].
+    fn dispatch(&mut self, op: Op, payload: &[u8]) -> Vec<u8> {
+        match op {
+            Op::Get => match Key::unpack(payload) {
+                Ok((key, _)) => Self::encode_value(self.layer.get(&key)),
+                Err(_) => vec![STATUS_ERR],
+            },
+            Op::Put => match Pair::unpack(payload) {
+                Ok((pair, _)) => Self::encode_value(self.layer.put(&pair.key, pair.value)),
+                Err(_) => vec![STATUS_ERR],
+            },
+            Op::Del => match Key::unpack(payload) {
+                Ok((key, _)) => {
+                    self.layer.del(&key);
+                    vec![STATUS_OK]
+                }
+                Err(_) => vec![STATUS_ERR],
+            },
+            Op::Forward | Op::Backward => unreachable!("streamed in Self::stream_pairs"),
+        }
+    }
+
+    fn encode_value(value: Option<Value>) -> Vec<u8> {
+        match value {
+            Some(value) => {
+                let mut data = vec![STATUS_OK];
+                data.extend(value.pack());
+                data
+            }
+            None => vec![STATUS_NONE],
+        }
+    }
+
+    /// Stream a forward/backward iteration as one `[STATUS_OK][Pair::pack()]`
+    /// frame per pair, followed by a final `[STATUS_NONE]` frame marking the
+    /// end of the stream, instead of buffering every pair into a single frame.
+    fn stream_pairs(
+        &mut self,
+        op: Op,
+        payload: &[u8],
+        stream: &mut TcpStream,
+    ) -> std::io::Result<()> {
+        let base = Key::unpack(payload).ok().map(|(key, _)| key);
+        let pairs = match op {
+            Op::Forward => self.layer.forward(base.as_ref()),
+            Op::Backward => self.layer.backward(base.as_ref()),
+            _ => unreachable!("only called for Op::Forward / Op::Backward"),
+        };
+
+        for (key, value) in pairs {
+            let mut frame = vec![STATUS_OK];
+            frame.extend(Pair::new(key, value).pack());
+            write_frame(stream, &frame)?;
+        }
+
+        write_frame(stream, &[STATUS_NONE])
+    }
+}
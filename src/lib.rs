@@ -9,5 +9,11 @@ mod layers;
 mod types;
 
 pub use errors::{Error, Result};
-pub use layers::{get_layer, Layer};
-pub use types::{Key, Value};
+pub use layers::{
+    get_async_layer, get_layer, AsyncClient, AsyncLayer, BatchOp, BlockingLayer, Layer, Server,
+    SyncClient, TcpClient,
+};
+pub use types::{CompactPacker, Converter, Key, Packer, Pair, Value};
+
+/// `#[derive(Packer)]` for structs whose fields already implement [`Packer`].
+pub use papyrus_derive::Packer;
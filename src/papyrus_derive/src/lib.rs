@@ -0,0 +1,64 @@
+//! The `#[derive(Packer)]` proc-macro companion to `papyrus::Packer`.
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields};
+
+/// Derive `papyrus::Packer` for a struct with named fields whose own types
+/// already implement `Packer`.
+///
+/// The generated `pack` concatenates each field's `pack()` output in
+/// declaration order, mirroring the pattern hand-written for `papyrus::Pair`.
+/// The generated `unpack` threads the remaining slice through each field in
+/// turn, so it composes with nested `#[derive(Packer)]` structs and with any
+/// `Packer` impl (e.g. `Vec<Value>`) exactly like a hand-written one would.
+#[proc_macro_derive(Packer)]
+pub fn derive_packer(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => panic!("Packer can only be derived for structs with named fields"),
+        },
+        _ => panic!("Packer can only be derived for structs"),
+    };
+
+    let field_names: Vec<_> = fields.iter().map(|field| field.ident.as_ref().unwrap()).collect();
+
+    let pack_fields = field_names.iter().map(|field| {
+        quote! {
+            data.extend_from_slice(&::papyrus::Packer::pack(&self.#field));
+        }
+    });
+
+    let unpack_fields = fields.iter().map(|field| {
+        let name = field.ident.as_ref().unwrap();
+        let ty = &field.ty;
+
+        quote! {
+            let (#name, next) = <#ty as ::papyrus::Packer>::unpack(rest)
+                .map_err(|_| ::papyrus::Error::InvalidArgument)?;
+            rest = next;
+        }
+    });
+
+    let expanded = quote! {
+        impl ::papyrus::Packer for #name {
+            fn pack(&self) -> Vec<u8> {
+                let mut data: Vec<u8> = Vec::new();
+                #(#pack_fields)*
+                data
+            }
+
+            fn unpack(data: &[u8]) -> ::papyrus::Result<(Self, &[u8])> {
+                let mut rest: &[u8] = data;
+                #(#unpack_fields)*
+
+                Ok((Self { #(#field_names),* }, rest))
+            }
+        }
+    };
+
+    TokenStream::from(expanded)
+}
@@ -0,0 +1,71 @@
+use papyrus::{Key, Packer, Value};
+
+#[derive(Packer, Debug, PartialEq)]
+struct Flat {
+    key: Key,
+    value: Value,
+}
+
+#[derive(Packer, Debug, PartialEq)]
+struct Nested {
+    name: Key,
+    flat: Flat,
+}
+
+#[derive(Packer, Debug, PartialEq)]
+struct WithSeq {
+    name: Key,
+    items: Vec<Value>,
+}
+
+#[test]
+fn test_derive_packer_round_trip_on_a_flat_struct() {
+    let orig = Flat {
+        key: "key".into(),
+        value: "value".into(),
+    };
+
+    let data = orig.pack();
+    let (decoded, rest) = Flat::unpack(&data).unwrap();
+
+    assert_eq!(orig, decoded);
+    assert_eq!(rest.len(), 0);
+}
+
+#[test]
+fn test_derive_packer_round_trip_on_a_nested_struct() {
+    let orig = Nested {
+        name: "outer".into(),
+        flat: Flat {
+            key: "inner".into(),
+            value: "value".into(),
+        },
+    };
+
+    let data = orig.pack();
+    let (decoded, rest) = Nested::unpack(&data).unwrap();
+
+    assert_eq!(orig, decoded);
+    assert_eq!(rest.len(), 0);
+}
+
+#[test]
+fn test_derive_packer_round_trip_on_a_struct_with_a_vec_field() {
+    let orig = WithSeq {
+        name: "list".into(),
+        items: vec!["a".into(), "b".into(), "c".into()],
+    };
+
+    let data = orig.pack();
+    let (decoded, rest) = WithSeq::unpack(&data).unwrap();
+
+    assert_eq!(orig, decoded);
+    assert_eq!(rest.len(), 0);
+}
+
+#[test]
+fn test_derive_packer_unpack_stops_on_a_malformed_field() {
+    let data: Vec<u8> = vec![];
+
+    assert_eq!(Flat::unpack(&data), Err(papyrus::Error::InvalidArgument));
+}
@@ -1,5 +1,6 @@
 //! The RevDB builder and constructor.
 use crate::settings::Settings;
+use papyrus::{get_layer, Layer};
 
 /// The RevDB builder.
 #[derive(Debug, Default)]
@@ -31,4 +32,16 @@ impl RevDB {
     pub fn settings(&self) -> &Settings {
         &self.settings
     }
+
+    /// Open the `sst://` layer at `path`, configured with this instance's
+    /// `sstable_block_entries`/`sstable_bloom_fpr` settings via the layer's
+    /// `?block=`/`?fpr=` query parameters.
+    pub fn open_sstable(&self, path: &str) -> Option<Box<dyn Layer>> {
+        let url = format!(
+            "sst://{}?block={}&fpr={}",
+            path, self.settings.sstable_block_entries, self.settings.sstable_bloom_fpr
+        );
+
+        get_layer(&url)
+    }
 }
@@ -15,4 +15,7 @@ pub enum Error {
 
     /// Stop the remaining execution
     StopExecution,
+
+    /// The configured layer could not be opened
+    LayerUnavailable,
 }
@@ -1,9 +1,13 @@
 //! RevDB: the embeddable, persistent, and revision storage.
 use atty::Stream;
+use base64::{engine::general_purpose::STANDARD, Engine};
 use clap::Parser as ClapParser;
+use papyrus::{get_layer, Layer, Packer, Pair};
+use pest::iterators::Pair as PestPair;
 use pest::Parser;
 use pest_derive::Parser as PestParser;
 use rustyline::{error::ReadlineError, DefaultEditor};
+use std::cell::RefCell;
 use tracing::{error, info, trace};
 
 use revdb::{Error, Result};
@@ -14,11 +18,23 @@ use revdb::{Error, Result};
 pub struct RevDBParser;
 
 /// The command-line tool for RevDB.
-#[derive(Debug, ClapParser)]
+#[derive(ClapParser)]
 #[command(author, version, about, long_about = None)]
 pub struct RevDBCli {
     #[clap(flatten)]
     verbose: clap_verbosity_flag::Verbosity,
+
+    #[clap(default_value = "mem://", help = "The URL of the active Layer.")]
+    url: String,
+
+    #[clap(skip)]
+    layer: RefCell<Option<Box<dyn Layer>>>,
+}
+
+impl std::fmt::Debug for RevDBCli {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RevDBCli").field("url", &self.url).finish()
+    }
 }
 
 impl RevDBCli {
@@ -32,19 +48,80 @@ impl RevDBCli {
     pub fn eval(&self, command: &str) -> Result<()> {
         trace!("eval command: {}", command);
 
-        match RevDBParser::parse(Rule::expression, command) {
+        let mut pairs = match RevDBParser::parse(Rule::expression, command) {
             Err(err) => {
                 info!("invalid syntax: {}", err);
                 return Err(Error::InvalidCommand);
             }
-            Ok(_) => {
-                // execute the command
-            }
+            Ok(pairs) => pairs,
+        };
+
+        let expression = pairs.next().ok_or(Error::InvalidCommand)?;
+        let command = expression
+            .into_inner()
+            .find(|pair| pair.as_rule() == Rule::command)
+            .ok_or(Error::InvalidCommand)?;
+        let command = command.into_inner().next().ok_or(Error::InvalidCommand)?;
+
+        match command.as_rule() {
+            Rule::dump_command => self.dump(),
+            Rule::load_command => self.load(command),
+            _ => Err(Error::InvalidCommand),
         }
+    }
+
+    /// encode every pair in the active Layer as base64 and print it.
+    fn dump(&self) -> Result<()> {
+        let mut data: Vec<u8> = Vec::new();
+
+        self.with_layer(|layer| {
+            for (key, value) in layer.iter() {
+                data.extend(Pair::new(key, value).pack());
+            }
+
+            Ok(())
+        })?;
+
+        println!("{}", STANDARD.encode(&data));
 
         Ok(())
     }
 
+    /// decode the given base64 text and replay every pair into the active Layer.
+    fn load(&self, command: PestPair<Rule>) -> Result<()> {
+        let encoded = command
+            .into_inner()
+            .next()
+            .ok_or(Error::InvalidCommand)?
+            .as_str();
+        let data = STANDARD
+            .decode(encoded)
+            .map_err(|_| Error::InvalidCommand)?;
+
+        self.with_layer(|layer| {
+            for pair in Pair::unpack_iter(&data) {
+                let pair = pair.map_err(|_| Error::InvalidCommand)?;
+                layer.put(&pair.key, pair.value);
+            }
+
+            Ok(())
+        })
+    }
+
+    /// run the given closure against the active Layer, opening it on first use.
+    fn with_layer<F>(&self, f: F) -> Result<()>
+    where
+        F: FnOnce(&mut Box<dyn Layer>) -> Result<()>,
+    {
+        let mut layer = self.layer.borrow_mut();
+
+        if layer.is_none() {
+            *layer = Some(get_layer(&self.url).ok_or(Error::LayerUnavailable)?);
+        }
+
+        f(layer.as_mut().expect("layer was just opened"))
+    }
+
     // ======== private methods ========
 
     /// execute revdb with the given arguments, and return the exit code.
@@ -1,38 +1,119 @@
 //! The global settings for RevDB.
 use serde::{Deserialize, Serialize};
+use serde_yaml::{Mapping, Value};
+use std::path::{Path, PathBuf};
 use tracing::warn;
 
 /// The global settings for RevDB based on YAML.
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Settings {
     /// to enable the debug information
+    #[serde(default)]
     pub debug: bool,
 
     /// the base directory for the storage
+    #[serde(default = "Settings::default_basedir")]
     pub base: String,
+
+    /// the number of pairs packed into each on-disk block of a `sst://`
+    /// layer, passed through as that layer's `?block=` query parameter.
+    #[serde(default = "Settings::default_sstable_block_entries")]
+    pub sstable_block_entries: usize,
+
+    /// the target Bloom filter false-positive rate for a `sst://` layer,
+    /// passed through as that layer's `?fpr=` query parameter.
+    #[serde(default = "Settings::default_sstable_bloom_fpr")]
+    pub sstable_bloom_fpr: f64,
 }
 
 impl Settings {
     /// Load the settings from the given file.
+    ///
+    /// The file is processed as a stack of layers: a `%include <path>` line
+    /// (resolved relative to the including file's directory) pulls in
+    /// another file's settings before this file's own keys are applied, and
+    /// a `%unset <key>` line drops a key inherited from an earlier layer so
+    /// it falls back to its default. Later layers win on conflict, matching
+    /// a base config composed with per-host overrides.
     pub fn load(path: Option<String>) -> Self {
         match path {
             None => Self::default(),
-            Some(path) => match std::fs::read_to_string(&path) {
-                Ok(content) => match serde_yaml::from_str(&content) {
-                    Ok(settings) => return settings,
-                    Err(err) => {
-                        warn!("failed to parse the settings: {}", err);
+            Some(path) => {
+                let mut chain = Vec::new();
+                match Self::load_layer(Path::new(&path), &mut chain) {
+                    Some(mapping) => match serde_yaml::from_value(Value::Mapping(mapping)) {
+                        Ok(settings) => settings,
+                        Err(err) => {
+                            warn!("failed to parse the settings: {}", err);
+
+                            Self::default()
+                        }
+                    },
+                    None => {
+                        warn!("failed to read the settings file: {}", &path);
 
                         Self::default()
                     }
-                },
-                Err(_) => {
-                    warn!("failed to read the settings file: {}", &path);
+                }
+            }
+        }
+    }
 
-                    Self::default()
+    /// Load `path` and any `%include`d layers into a single merged mapping,
+    /// applying `%unset` directives once every layer is merged. Returns
+    /// `None` only when `path` itself cannot be read; a missing `%include`
+    /// target just warns and contributes nothing, matching the crate's
+    /// lenient fallback behavior.
+    fn load_layer(path: &Path, chain: &mut Vec<PathBuf>) -> Option<Mapping> {
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        if chain.contains(&canonical) {
+            warn!("circular %include detected at {}", path.display());
+            return Some(Mapping::new());
+        }
+
+        let content = std::fs::read_to_string(path).ok()?;
+        let dir = path.parent().unwrap_or_else(|| Path::new("."));
+
+        chain.push(canonical);
+
+        let mut merged = Mapping::new();
+        let mut unsets: Vec<String> = Vec::new();
+        let mut body = String::new();
+
+        for line in content.lines() {
+            match line.trim() {
+                directive if directive.starts_with("%include ") => {
+                    let include_path = dir.join(directive["%include ".len()..].trim());
+                    if let Some(layer) = Self::load_layer(&include_path, chain) {
+                        merged.extend(layer);
+                    } else {
+                        warn!("failed to read the included settings file: {}", include_path.display());
+                    }
                 }
-            },
+                directive if directive.starts_with("%unset ") => {
+                    unsets.push(directive["%unset ".len()..].trim().to_string());
+                }
+                _ => {
+                    body.push_str(line);
+                    body.push('\n');
+                }
+            }
+        }
+
+        chain.pop();
+
+        match serde_yaml::from_str::<Value>(&body) {
+            Ok(Value::Mapping(own)) => merged.extend(own),
+            Ok(Value::Null) => {}
+            Ok(_) => warn!("settings file {} is not a mapping", path.display()),
+            Err(err) => warn!("failed to parse the settings: {}", err),
+        }
+
+        for key in unsets {
+            merged.remove(&Value::String(key));
         }
+
+        Some(merged)
     }
 
     /// Get the default base directory.
@@ -42,6 +123,18 @@ impl Settings {
             None => ".revdb".to_string(),
         }
     }
+
+    /// Get the default number of pairs per `sst://` block, matching
+    /// `papyrus`'s own default.
+    fn default_sstable_block_entries() -> usize {
+        32
+    }
+
+    /// Get the default target Bloom filter false-positive rate for a
+    /// `sst://` layer, matching `papyrus`'s own default.
+    fn default_sstable_bloom_fpr() -> f64 {
+        0.01
+    }
 }
 
 impl Default for Settings {
@@ -49,6 +142,8 @@ impl Default for Settings {
         Self {
             debug: false,
             base: Self::default_basedir(),
+            sstable_block_entries: Self::default_sstable_block_entries(),
+            sstable_bloom_fpr: Self::default_sstable_bloom_fpr(),
         }
     }
 }
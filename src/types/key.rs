@@ -1,5 +1,7 @@
 //! Key is the searchable and sortable data type used in Papyrus.
-use crate::{Converter, Error, Packer, Result};
+use crate::types::traits::{cbor_decode_header, cbor_encode_header};
+use crate::types::traits::{CBOR_MAJOR_BOOL, CBOR_MAJOR_BYTES, CBOR_MAJOR_NEGINT, CBOR_MAJOR_UID, CBOR_MAJOR_UINT};
+use crate::{CompactPacker, Converter, Error, Packer, Result};
 use std::convert::From;
 use tracing::{error, warn};
 
@@ -77,76 +79,153 @@ impl From<&str> for Key {
     }
 }
 
+// ======== the memcomparable type tags ========
+const TAG_BOOL: u8 = 0x00;
+const TAG_INT: u8 = 0x01;
+const TAG_UID: u8 = 0x02;
+const TAG_STR: u8 = 0x03;
+const TAG_TEXT: u8 = 0x04;
+
+/// Escape a string so its memcomparable encoding is never a prefix of another
+/// key's: every embedded `0x00` becomes `0x00 0xFF`, and the whole thing ends
+/// with a `0x00 0x00` terminator that can't occur inside the escaped body.
+fn escape_string(s: &str) -> Vec<u8> {
+    let mut data = Vec::with_capacity(s.len() + 2);
+
+    for &byte in s.as_bytes() {
+        data.push(byte);
+        if byte == 0x00 {
+            data.push(0xFF);
+        }
+    }
+    data.extend_from_slice(&[0x00, 0x00]);
+
+    data
+}
+
+/// Reverse [`escape_string`], returning the decoded string and the number of
+/// bytes consumed (including the terminator).
+fn unescape_string(data: &[u8]) -> Result<(String, usize)> {
+    let mut raw = Vec::new();
+    let mut index = 0;
+
+    loop {
+        match data.get(index) {
+            Some(0x00) => match data.get(index + 1) {
+                Some(0xFF) => {
+                    raw.push(0x00);
+                    index += 2;
+                }
+                Some(0x00) => {
+                    index += 2;
+                    let s = String::from_utf8(raw).map_err(|_| Error::InvalidArgument)?;
+                    return Ok((s, index));
+                }
+                _ => return Err(Error::InvalidArgument),
+            },
+            Some(&byte) => {
+                raw.push(byte);
+                index += 1;
+            }
+            None => return Err(Error::InvalidArgument),
+        }
+    }
+}
+
 // ======== the converter ========
 impl Converter for Key {
-    /// the capacity of the type.
+    /// the capacity of the type, i.e. the length of its memcomparable encoding.
     fn cap(&self) -> usize {
-        match self {
-            Key::BOOL(_) => 1,
-            Key::INT(_) => 8,
-            Key::UID(_) => 16,
-            Key::STR(_) => 64,
-            Key::TEXT(_) => 256,
-        }
+        self.to_bytes().len()
     }
 
-    /// Convert the type into binary format. It only contains the data of the type
-    /// itself, not including the type information.
+    /// Convert the type into its memcomparable binary format: a leading
+    /// type-tag byte so keys of different types sort in a well-defined order,
+    /// followed by a payload whose raw byte comparison matches the logical
+    /// order of the value. `INT` flips the sign bit so negatives sort before
+    /// positives under unsigned comparison; `STR`/`TEXT` escape embedded NUL
+    /// bytes and end with a terminator so no key is a prefix of another.
     fn to_bytes(&self) -> Vec<u8> {
-        let mut data: Vec<u8> = match self {
-            Key::BOOL(b) => vec![*b as u8],
-            Key::INT(i) => i.to_ne_bytes().to_vec(),
-            Key::UID(uid) => uid.to_ne_bytes().to_vec(),
-            Key::STR(s) => s.as_bytes().to_vec(),
-            Key::TEXT(s) => s.as_bytes().to_vec(),
-        };
-
-        data.extend(vec![0; self.cap() - data.len()]);
-        data
+        match self {
+            Key::BOOL(b) => vec![TAG_BOOL, *b as u8],
+            Key::INT(i) => {
+                let flipped = (*i as u64) ^ 0x8000_0000_0000_0000;
+                let mut data = vec![TAG_INT];
+                data.extend_from_slice(&flipped.to_be_bytes());
+                data
+            }
+            Key::UID(uid) => {
+                let mut data = vec![TAG_UID];
+                data.extend_from_slice(&uid.to_be_bytes());
+                data
+            }
+            Key::STR(s) => {
+                let mut data = vec![TAG_STR];
+                data.extend(escape_string(s));
+                data
+            }
+            Key::TEXT(s) => {
+                let mut data = vec![TAG_TEXT];
+                data.extend(escape_string(s));
+                data
+            }
+        }
     }
 
-    /// Convert from binary format to the type. It only contains the data of the type
-    /// itself, not including the type information.
+    /// Convert from the memcomparable binary format written by [`Key::to_bytes`]
+    /// back into the type. Unlike the general `Converter` contract, the type tag
+    /// is part of this data (it is what makes the encoding order-preserving
+    /// across types), so `data` must be exactly one key's encoding.
     fn from_bytes(data: &[u8]) -> Result<Self> {
-        match data.len() {
-            1 => Ok(Key::BOOL(data[0] != 0)),
-            8 => {
-                let mut buf = [0u8; 8];
-                buf.copy_from_slice(data);
-                Ok(Key::INT(i64::from_ne_bytes(buf)))
-            }
-            16 => {
-                let mut buf = [0u8; 16];
-                buf.copy_from_slice(data);
-                Ok(Key::UID(u128::from_ne_bytes(buf)))
-            }
-            64 | 256 => match data.iter().rposition(|&b| b != 0) {
-                Some(index) => {
-                    let s = String::from_utf8_lossy(&data[..index + 1]).to_string();
-                    Ok(Key::STR(s))
-                }
-                None => Ok(Key::STR("".to_string())),
-            },
-            _ => Err(Error::InvalidArgument),
+        let (&tag, rest) = data.split_first().ok_or(Error::InvalidArgument)?;
+        decode_payload(tag, rest)
+    }
+}
+
+/// Decode the payload following a memcomparable type tag into the `Key` it encodes.
+fn decode_payload(tag: u8, rest: &[u8]) -> Result<Key> {
+    match tag {
+        TAG_BOOL => {
+            let &byte = rest.first().ok_or(Error::InvalidArgument)?;
+            Ok(Key::BOOL(byte != 0))
+        }
+        TAG_INT => {
+            let buf: [u8; 8] = rest
+                .get(..8)
+                .ok_or(Error::InvalidArgument)?
+                .try_into()
+                .unwrap();
+            let flipped = u64::from_be_bytes(buf);
+            Ok(Key::INT((flipped ^ 0x8000_0000_0000_0000) as i64))
+        }
+        TAG_UID => {
+            let buf: [u8; 16] = rest
+                .get(..16)
+                .ok_or(Error::InvalidArgument)?
+                .try_into()
+                .unwrap();
+            Ok(Key::UID(u128::from_be_bytes(buf)))
+        }
+        TAG_STR => {
+            let (s, _) = unescape_string(rest)?;
+            Ok(Key::STR(s))
         }
+        TAG_TEXT => {
+            let (s, _) = unescape_string(rest)?;
+            Ok(Key::TEXT(s))
+        }
+        _ => Err(Error::InvalidArgument),
     }
 }
 
 impl Packer for Key {
     /// Convert the type into binary format with type information.
+    ///
+    /// The memcomparable [`Converter::to_bytes`] already carries its own type
+    /// tag, so `pack` is just `to_bytes`; only `unpack` needs to work out how
+    /// many bytes belong to this key once more data may follow it.
     fn pack(&self) -> Vec<u8> {
-        let typ: u8 = match self {
-            Key::BOOL(_) => 0,
-            Key::INT(_) => 1,
-            Key::UID(_) => 2,
-            Key::STR(_) => 3,
-            Key::TEXT(_) => 4,
-        };
-
-        let mut data = vec![typ];
-
-        data.extend(self.to_bytes());
-        data
+        self.to_bytes()
     }
 
     /// Convert from binary format to the type, which the binary format contains the
@@ -155,17 +234,16 @@ impl Packer for Key {
     where
         Self: Sized,
     {
-        if data.len() < 2 {
-            return Err(Error::InvalidArgument);
-        }
-
-        let rest: &[u8] = &data[1..];
-        let size: usize = match data[0] {
-            0 => 1,
-            1 => 8,
-            2 => 16,
-            3 => 64,
-            4 => 256,
+        let (&tag, rest) = data.split_first().ok_or(Error::InvalidArgument)?;
+
+        let size: usize = match tag {
+            TAG_BOOL => 1,
+            TAG_INT => 8,
+            TAG_UID => 16,
+            TAG_STR | TAG_TEXT => {
+                let (_, consumed) = unescape_string(rest)?;
+                consumed
+            }
             _ => return Err(Error::InvalidArgument),
         };
 
@@ -178,13 +256,72 @@ impl Packer for Key {
             return Err(Error::InvalidArgument);
         }
 
-        let (data, rest) = rest.split_at(size);
-        let key = Key::from_bytes(data)?;
+        let (payload, rest) = rest.split_at(size);
+        let key = decode_payload(tag, payload)?;
 
         Ok((key, rest))
     }
 }
 
+impl CompactPacker for Key {
+    /// Convert the type into the compact binary format.
+    fn pack_compact(&self) -> Vec<u8> {
+        match self {
+            Key::BOOL(b) => cbor_encode_header(CBOR_MAJOR_BOOL, *b as u64),
+            Key::INT(i) if *i >= 0 => cbor_encode_header(CBOR_MAJOR_UINT, *i as u64),
+            Key::INT(i) => cbor_encode_header(CBOR_MAJOR_NEGINT, (-1 - *i) as u64),
+            Key::UID(uid) => {
+                let raw = uid.to_be_bytes();
+                let mut data = cbor_encode_header(CBOR_MAJOR_UID, raw.len() as u64);
+                data.extend_from_slice(&raw);
+                data
+            }
+            Key::STR(s) | Key::TEXT(s) => {
+                let raw = s.as_bytes();
+                let mut data = cbor_encode_header(CBOR_MAJOR_BYTES, raw.len() as u64);
+                data.extend_from_slice(raw);
+                data
+            }
+        }
+    }
+
+    /// Convert from the compact binary format back into the type, returning the
+    /// unconsumed remainder of `data`.
+    fn unpack_compact(data: &[u8]) -> Result<(Self, &[u8])> {
+        let (major, value, header_len) = cbor_decode_header(data)?;
+        let rest = &data[header_len..];
+
+        match major {
+            CBOR_MAJOR_BOOL => Ok((Key::BOOL(value != 0), rest)),
+            CBOR_MAJOR_UINT => Ok((Key::INT(value as i64), rest)),
+            CBOR_MAJOR_NEGINT => Ok((Key::INT(-1 - value as i64), rest)),
+            CBOR_MAJOR_BYTES => {
+                let size = value as usize;
+                if rest.len() < size {
+                    return Err(Error::InvalidArgument);
+                }
+
+                let (raw, rest) = rest.split_at(size);
+                let key = Key::from(String::from_utf8_lossy(raw).to_string().as_str());
+
+                Ok((key, rest))
+            }
+            CBOR_MAJOR_UID => {
+                let size = value as usize;
+                if size != 16 || rest.len() < size {
+                    return Err(Error::InvalidArgument);
+                }
+
+                let (raw, rest) = rest.split_at(size);
+                let buf: [u8; 16] = raw.try_into().unwrap();
+
+                Ok((Key::UID(u128::from_be_bytes(buf)), rest))
+            }
+            _ => Err(Error::InvalidArgument),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -225,6 +362,57 @@ mod tests {
         assert_eq!(Key::from_bytes(&v), Err(Error::InvalidArgument));
     }
 
+    #[test]
+    fn test_key_convert_is_big_endian() {
+        // hard-coded tag + big-endian-with-flipped-sign-bit layout, independent
+        // of the host endianness
+        let int_data: Vec<u8> = vec![TAG_INT, 0x80, 0, 0, 0, 0, 0, 0x12, 0x34];
+        assert_eq!(Key::from_bytes(&int_data), Ok(Key::INT(0x1234)));
+
+        let uid_data: Vec<u8> = vec![
+            TAG_UID, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x12, 0x34,
+        ];
+        assert_eq!(Key::from_bytes(&uid_data), Ok(Key::UID(0x1234)));
+    }
+
+    #[test]
+    fn test_key_to_bytes_is_memcomparable_for_ints() {
+        let values: Vec<i64> = vec![i64::MIN, -1234, -1, 0, 1, 1234, i64::MAX];
+
+        for window in values.windows(2) {
+            let (a, b) = (Key::INT(window[0]), Key::INT(window[1]));
+            assert!(a < b);
+            assert!(a.to_bytes() < b.to_bytes());
+        }
+    }
+
+    #[test]
+    fn test_key_to_bytes_is_memcomparable_for_strings() {
+        let values: Vec<&str> = vec!["", "a", "aa", "ab", "b"];
+
+        for window in values.windows(2) {
+            let (a, b): (Key, Key) = (window[0].into(), window[1].into());
+            assert!(a < b);
+            assert!(a.to_bytes() < b.to_bytes());
+        }
+    }
+
+    #[test]
+    fn test_key_to_bytes_round_trips_string_with_embedded_nul() {
+        let key: Key = Key::STR("a\0b".to_string());
+        let data = key.to_bytes();
+
+        assert_eq!(Key::from_bytes(&data), Ok(key));
+    }
+
+    #[test]
+    fn test_key_to_bytes_no_key_is_a_prefix_of_another() {
+        let short: Key = "a".into();
+        let long: Key = "ab".into();
+
+        assert!(!long.to_bytes().starts_with(&short.to_bytes()));
+    }
+
     test_key_convert!(BOOL, true);
     test_key_convert!(BOOL, false);
     test_key_convert!(INT, 0);
@@ -238,6 +426,60 @@ mod tests {
     test_key_convert!(STR, "a");
     test_key_convert!(STR, "aaaaaa");
 
+    macro_rules! test_key_compact_packer {
+        ($type:ident, $value:expr) => {
+            paste! {
+                #[test]
+                fn [<test_key_compact_packer_ $type:lower _ $value>]() {
+                    let key: Key = $value.into();
+                    let rest: &[u8] = &[];
+
+                    assert_eq!(Key::unpack_compact(&key.pack_compact()), Ok((key, rest)));
+                }
+            }
+        };
+    }
+
+    test_key_compact_packer!(BOOL, true);
+    test_key_compact_packer!(BOOL, false);
+    test_key_compact_packer!(INT, 0);
+    test_key_compact_packer!(INT, 1);
+    test_key_compact_packer!(INT, 65535);
+    test_key_compact_packer!(INT, 4294967295i64);
+    test_key_compact_packer!(STR, "");
+    test_key_compact_packer!(STR, "a");
+    test_key_compact_packer!(STR, "aaaaaa");
+    test_key_compact_packer!(UID, 0u128);
+    test_key_compact_packer!(UID, 340282366920938463463374607431768211455u128);
+
+    #[test]
+    fn test_key_compact_packer_16_byte_string_is_not_confused_with_uid() {
+        let raw = "sixteen-char-key";
+        assert_eq!(raw.len(), 16);
+
+        let key: Key = raw.into();
+        let packed = key.pack_compact();
+        let (decoded, rest) = Key::unpack_compact(&packed).unwrap();
+
+        assert_eq!(decoded, key);
+        assert!(rest.is_empty());
+    }
+
+    #[test]
+    fn test_key_compact_packer_negative_int() {
+        let key: Key = Key::INT(-1234);
+        let rest: &[u8] = &[];
+
+        assert_eq!(Key::unpack_compact(&key.pack_compact()), Ok((key, rest)));
+    }
+
+    #[test]
+    fn test_key_compact_packer_is_smaller_than_fixed_width() {
+        let key: Key = "a".into();
+
+        assert!(key.pack_compact().len() < key.pack().len());
+    }
+
     macro_rules! test_key_unpack_iter {
         ($count:expr) => {
             paste! {
@@ -4,9 +4,11 @@
 //! the search and sortable Key and arbitrary length Value.
 
 mod key;
+mod pair;
 mod traits;
 mod value;
 
 pub use key::Key;
-pub use traits::Converter;
+pub use pair::Pair;
+pub use traits::{CompactPacker, Converter, Packer};
 pub use value::Value;
@@ -0,0 +1,103 @@
+//! The key-value pair as type in Papyrus.
+use crate::{CompactPacker, Key, Packer, Result, Value};
+
+/// The key-value pair as type in Papyrus.
+///
+/// It is the syntax sugar for the tuple of `(Key, Value)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pair {
+    pub key: Key,
+    pub value: Value,
+}
+
+impl Pair {
+    pub fn new(key: Key, value: Value) -> Self {
+        Self { key, value }
+    }
+}
+
+impl Packer for Pair {
+    /// Convert the type into binary format with type information.
+    fn pack(&self) -> Vec<u8> {
+        let mut data: Vec<u8> = Vec::new();
+
+        data.extend_from_slice(&self.key.pack());
+        data.extend_from_slice(&self.value.pack());
+
+        data
+    }
+
+    /// Convert from binary format to the type, which the binary format contains the
+    /// type information.
+    fn unpack(data: &[u8]) -> Result<(Self, &[u8])> {
+        let mut rest: &[u8] = data;
+        let key: Key;
+        let value: Value;
+
+        (key, rest) = Key::unpack(rest)?;
+        (value, rest) = Value::unpack(rest)?;
+
+        Ok((Self { key, value }, rest))
+    }
+}
+
+impl CompactPacker for Pair {
+    /// Convert the type into the compact binary format.
+    fn pack_compact(&self) -> Vec<u8> {
+        let mut data: Vec<u8> = Vec::new();
+
+        data.extend_from_slice(&self.key.pack_compact());
+        data.extend_from_slice(&self.value.pack_compact());
+
+        data
+    }
+
+    /// Convert from the compact binary format back into the type, returning the
+    /// unconsumed remainder of `data`.
+    fn unpack_compact(data: &[u8]) -> Result<(Self, &[u8])> {
+        let mut rest: &[u8] = data;
+        let key: Key;
+        let value: Value;
+
+        (key, rest) = Key::unpack_compact(rest)?;
+        (value, rest) = Value::unpack_compact(rest)?;
+
+        Ok((Self { key, value }, rest))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pair_packer() {
+        let key: Key = "key".into();
+        let value: Value = "value".into();
+
+        let orig: Pair = Pair::new(key.clone(), value.clone());
+        let data: Vec<u8> = orig.pack();
+
+        let (pair, rest) = Pair::unpack(&data).unwrap();
+
+        assert_eq!(orig, pair);
+        assert_eq!(rest.len(), 0);
+        assert_eq!(pair.key, key);
+        assert_eq!(pair.value, value);
+    }
+
+    #[test]
+    fn test_pair_compact_packer() {
+        let key: Key = "key".into();
+        let value: Value = "value".into();
+
+        let orig: Pair = Pair::new(key.clone(), value.clone());
+        let data: Vec<u8> = orig.pack_compact();
+
+        let (pair, rest) = Pair::unpack_compact(&data).unwrap();
+
+        assert_eq!(orig, pair);
+        assert_eq!(rest.len(), 0);
+        assert!(data.len() < orig.pack().len());
+    }
+}
@@ -1,5 +1,5 @@
 //! The abstraction of Type.
-use crate::Result;
+use crate::{Error, Result};
 
 /// The type converter that can convert between type and binary format.
 pub trait Converter {
@@ -30,7 +30,96 @@ pub trait Packer {
 
     /// Convert from binary format into the iterate of the type as many as possible,
     /// which the binary format contains the type information.
-    fn unpack_iter(data: &[u8]) -> Box<dyn Iterator<Item = Self> + '_>
+    ///
+    /// Unlike silently stopping at the first malformed item, this surfaces it
+    /// as an `Err`, then ends the iteration -- so callers can tell a clean
+    /// end-of-input (the stream simply runs out) from corruption mid-stream
+    /// (the last item is `Some(Err(_))`, not `None`).
+    fn unpack_iter(data: &[u8]) -> Box<dyn Iterator<Item = Result<Self>> + '_>
+    where
+        Self: Sized,
+    {
+        let mut remains: &[u8] = data;
+        let mut done = false;
+
+        Box::new(std::iter::from_fn(move || {
+            if done || remains.is_empty() {
+                return None;
+            }
+
+            match Self::unpack(remains) {
+                Ok((value, rest)) => {
+                    remains = rest;
+                    Some(Ok(value))
+                }
+                Err(err) => {
+                    done = true;
+                    Some(Err(err))
+                }
+            }
+        }))
+    }
+}
+
+/// A vector of `Packer` items is itself a `Packer`: an 8-byte big-endian
+/// count, followed by each item's own `pack()` output back-to-back, matching
+/// every other fixed-width integer in this codebase. This is what
+/// `#[derive(Packer)]` relies on for a field such as `Vec<Value>`.
+impl<T: Packer> Packer for Vec<T> {
+    fn pack(&self) -> Vec<u8> {
+        let mut data = (self.len() as u64).to_be_bytes().to_vec();
+
+        for item in self {
+            data.extend(item.pack());
+        }
+
+        data
+    }
+
+    fn unpack(data: &[u8]) -> Result<(Self, &[u8])> {
+        let buf: [u8; 8] = data.get(..8).ok_or(Error::InvalidArgument)?.try_into().unwrap();
+        let count = u64::from_be_bytes(buf);
+        let mut rest = &data[8..];
+
+        // each item packs to at least one byte, so a count claiming more
+        // items than `rest` has bytes left can only be corrupt data; reject
+        // it before `Vec::with_capacity` allocates for it.
+        if count > rest.len() as u64 {
+            return Err(Error::InvalidArgument);
+        }
+
+        let mut items = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            let item;
+            (item, rest) = T::unpack(rest)?;
+            items.push(item);
+        }
+
+        Ok((items, rest))
+    }
+}
+
+/// The compact, CBOR-inspired counterpart of [`Packer`].
+///
+/// Where `Packer` writes each type's own tagged encoding (for `Key`, a tag
+/// byte and an order-preserving body whose strings end in a two-byte
+/// terminator), `CompactPacker` leads with a single header byte
+/// `(major_type << 5) | info`, where `info` in `0..=23` carries the value
+/// inline and `24..=27` mean "1/2/4/8 big-endian bytes follow". It is opt-in
+/// per layer rather than the default.
+pub trait CompactPacker {
+    /// Convert the type into the compact binary format.
+    fn pack_compact(&self) -> Vec<u8>;
+
+    /// Convert from the compact binary format back into the type, returning the
+    /// unconsumed remainder of `data`.
+    fn unpack_compact(data: &[u8]) -> Result<(Self, &[u8])>
+    where
+        Self: Sized;
+
+    /// Convert from the compact binary format into the iterate of the type as
+    /// many as possible.
+    fn unpack_compact_iter(data: &[u8]) -> Box<dyn Iterator<Item = Self> + '_>
     where
         Self: Sized,
     {
@@ -41,10 +130,92 @@ pub trait Packer {
                 return None;
             }
 
-            let (value, rest) = Self::unpack(remains).ok()?;
+            let (value, rest) = Self::unpack_compact(remains).ok()?;
             remains = rest;
 
             Some(value)
         }))
     }
 }
+
+// ======== CBOR-style compact header ========
+/// the major types used by [`CompactPacker`], following CBOR's scheme.
+pub(crate) const CBOR_MAJOR_UINT: u8 = 0;
+pub(crate) const CBOR_MAJOR_NEGINT: u8 = 1;
+pub(crate) const CBOR_MAJOR_BYTES: u8 = 2;
+pub(crate) const CBOR_MAJOR_BOOL: u8 = 3;
+
+/// escape hatch for the `Value` variants that do not map onto a dedicated
+/// major type: the header's `value` carries the length of a nested
+/// [`Converter`](crate::Converter)-encoded (tag byte + body) blob.
+pub(crate) const CBOR_MAJOR_EXT: u8 = 4;
+
+/// `Key::UID`'s own major type, kept distinct from [`CBOR_MAJOR_BYTES`] (used
+/// for `Key::STR`/`TEXT`) so a 16-byte/16-character string key can never be
+/// mistaken for a `Key::UID` on decode.
+pub(crate) const CBOR_MAJOR_UID: u8 = 5;
+
+/// Encode a `(major_type, value)` pair into a CBOR-style header: the first byte
+/// is `(major_type << 5) | info`, where `info` 0-23 carries `value` inline and
+/// 24/25/26/27 mean "1/2/4/8 big-endian bytes follow".
+pub(crate) fn cbor_encode_header(major: u8, value: u64) -> Vec<u8> {
+    match value {
+        0..=23 => vec![(major << 5) | (value as u8)],
+        24..=0xFF => vec![(major << 5) | 24, value as u8],
+        0x100..=0xFFFF => {
+            let mut data = vec![(major << 5) | 25];
+            data.extend_from_slice(&(value as u16).to_be_bytes());
+            data
+        }
+        0x1_0000..=0xFFFF_FFFF => {
+            let mut data = vec![(major << 5) | 26];
+            data.extend_from_slice(&(value as u32).to_be_bytes());
+            data
+        }
+        _ => {
+            let mut data = vec![(major << 5) | 27];
+            data.extend_from_slice(&value.to_be_bytes());
+            data
+        }
+    }
+}
+
+/// Decode a CBOR-style header, returning `(major_type, value, header_len)`.
+pub(crate) fn cbor_decode_header(data: &[u8]) -> Result<(u8, u64, usize)> {
+    let head = *data.first().ok_or(Error::InvalidArgument)?;
+    let major = head >> 5;
+    let info = head & 0x1F;
+
+    match info {
+        0..=23 => Ok((major, info as u64, 1)),
+        24 => {
+            let byte = *data.get(1).ok_or(Error::InvalidArgument)?;
+            Ok((major, byte as u64, 2))
+        }
+        25 => {
+            let buf: [u8; 2] = data
+                .get(1..3)
+                .ok_or(Error::InvalidArgument)?
+                .try_into()
+                .unwrap();
+            Ok((major, u16::from_be_bytes(buf) as u64, 3))
+        }
+        26 => {
+            let buf: [u8; 4] = data
+                .get(1..5)
+                .ok_or(Error::InvalidArgument)?
+                .try_into()
+                .unwrap();
+            Ok((major, u32::from_be_bytes(buf) as u64, 5))
+        }
+        27 => {
+            let buf: [u8; 8] = data
+                .get(1..9)
+                .ok_or(Error::InvalidArgument)?
+                .try_into()
+                .unwrap();
+            Ok((major, u64::from_be_bytes(buf), 9))
+        }
+        _ => Err(Error::InvalidArgument),
+    }
+}
@@ -1,13 +1,19 @@
 //! Value is the arbitrary length data used in Papyrus.
-use crate::{Converter, Error, Packer, Result};
+use crate::types::traits::{cbor_decode_header, cbor_encode_header};
+use crate::types::traits::{CBOR_MAJOR_BOOL, CBOR_MAJOR_BYTES, CBOR_MAJOR_EXT};
+use crate::{CompactPacker, Converter, Error, Packer, Result};
+use std::collections::BTreeMap;
 use std::convert::From;
+use std::str::FromStr;
 use tracing::{trace, warn};
 
 /// Value is the arbitrary length data used in Papyrus.
 ///
-/// It is the arbitrary length data upload from user and stored in the
-/// Papyrus. It can be any binary data and may compressed or store as
-/// another detached file.
+/// Following the Preserves approach of one data model with matching text and
+/// binary syntaxes, `Value` is a small tagged union rather than a bare byte
+/// string: scalars (`BOOL`/`UINT`/`INT`/`FLOAT`/`STR`/`BYTES32`), opaque `RAW`
+/// bytes for backward compatibility with callers that only care about byte
+/// content, and the compound `SEQ`/`DICT` containers for structured documents.
 #[derive(Debug, Clone, PartialEq)]
 pub enum Value {
     /// The empty value.
@@ -16,17 +22,51 @@ pub enum Value {
     /// The marked as deleted value.
     DELETED,
 
+    /// A boolean.
+    BOOL(bool),
+
+    /// An unsigned integer.
+    UINT(u64),
+
+    /// A signed integer.
+    INT(i64),
+
+    /// A floating point number.
+    FLOAT(f64),
+
+    /// A fixed 32-byte string, e.g. a hash or a public key. Unlike `RAW`, its
+    /// width is known ahead of time so the encoding skips the length field.
+    BYTES32([u8; 32]),
+
     /// The raw binary data.
     RAW(Vec<u8>),
+
+    /// A UTF-8 string.
+    STR(String),
+
+    /// An ordered sequence of values.
+    SEQ(Vec<Value>),
+
+    /// A string-keyed dictionary of values, ordered by key.
+    DICT(BTreeMap<String, Value>),
 }
 
 impl Value {
-    /// The total size of the raw value.
+    /// The total size of the value: the byte length for `RAW`/`STR`, the
+    /// number of entries for `SEQ`/`DICT`, and 0 for every other variant.
     pub fn len(&self) -> usize {
         match self {
-            Value::EMPTY => 0,
-            Value::DELETED => 0,
+            Value::EMPTY
+            | Value::DELETED
+            | Value::BOOL(_)
+            | Value::UINT(_)
+            | Value::INT(_)
+            | Value::FLOAT(_)
+            | Value::BYTES32(_) => 0,
             Value::RAW(data) => data.len(),
+            Value::STR(data) => data.len(),
+            Value::SEQ(items) => items.len(),
+            Value::DICT(map) => map.len(),
         }
     }
 
@@ -34,6 +74,22 @@ impl Value {
     pub fn delete(&mut self) {
         *self = Value::DELETED
     }
+
+    /// Borrow the value as an `i64`, or `None` if it isn't an `INT`.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Value::INT(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Borrow the value as a `bool`, or `None` if it isn't a `BOOL`.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::BOOL(value) => Some(*value),
+            _ => None,
+        }
+    }
 }
 
 // ======== value-to-value conversions ========
@@ -61,83 +117,394 @@ impl From<String> for Value {
     }
 }
 
-// ======== the converter ========
-impl Converter for Value {
-    /// the capacity of the type.
-    fn cap(&self) -> usize {
-        let header_size: usize = 4;
-        let body_size: usize = match self {
-            Value::EMPTY | Value::DELETED => 0,
-            Value::RAW(data) => data.len(),
-        };
+impl From<bool> for Value {
+    fn from(data: bool) -> Self {
+        Value::BOOL(data)
+    }
+}
 
-        header_size + body_size
+impl From<u64> for Value {
+    fn from(data: u64) -> Self {
+        Value::UINT(data)
     }
+}
 
-    /// Convert the type into binary format.
-    ///
-    /// 0       8      16     24     32
-    /// +------+------+------+------+
-    /// | TYPE |       SIZE         |
-    /// +---------------------------+
-    /// ~                           ~
-    /// ~          DATA             ~
-    /// ~                           ~
-    /// +---------------------------+
-    fn to_bytes(&self) -> Vec<u8> {
-        let typ: u8 = match self {
-            Value::EMPTY => 0,
-            Value::DELETED => 1,
-            Value::RAW(_) => 2,
-        };
-        let header: u32 = (typ as u32) << 24 | (self.len() as u32) & 0x00FFFFFF;
-        let mut data: Vec<u8> = header.to_le_bytes().to_vec();
+impl From<i64> for Value {
+    fn from(data: i64) -> Self {
+        Value::INT(data)
+    }
+}
 
-        match self {
-            Value::EMPTY => {}
-            Value::DELETED => {}
-            Value::RAW(raw) => data.extend(raw),
+impl From<f64> for Value {
+    fn from(data: f64) -> Self {
+        Value::FLOAT(data)
+    }
+}
+
+impl From<[u8; 32]> for Value {
+    fn from(data: [u8; 32]) -> Self {
+        Value::BYTES32(data)
+    }
+}
+
+// ======== the varint helpers ========
+/// Encode `value` as a little-endian base-128 varint (LEB128).
+fn encode_varint(mut value: u64) -> Vec<u8> {
+    let mut data = Vec::new();
+
+    loop {
+        let byte = (value & 0x7F) as u8;
+        value >>= 7;
+
+        if value == 0 {
+            data.push(byte);
+            break;
         }
 
-        data
+        data.push(byte | 0x80);
     }
 
-    /// Convert from binary format to the type. It only contains the data of the type
-    /// itself, not including the type information.
-    fn from_bytes(data: &[u8]) -> Result<Self> {
-        if data.len() < 4 {
-            warn!("cannot convert value from {:?}", data);
+    data
+}
+
+/// Decode a varint written by [`encode_varint`], returning `(value, consumed)`.
+fn decode_varint(data: &[u8]) -> Result<(u64, usize)> {
+    let mut value: u64 = 0;
+
+    for (index, byte) in data.iter().enumerate() {
+        let shift = index * 7;
+        if shift >= 64 {
+            warn!("varint too long in {:?}", data);
             return Err(Error::InvalidArgument);
         }
 
-        let header = u32::from_le_bytes(data[0..4].try_into().unwrap());
-        let size = (header & 0x00FFFFFF) as usize;
+        value |= ((byte & 0x7F) as u64) << shift;
 
-        match ((header >> 24), size) {
-            (0, 0) => Ok(Value::EMPTY),
-            (1, 0) => Ok(Value::DELETED),
-            (2, size) => {
-                if data.len() < size + 4 {
-                    warn!(
-                        "cannot convert value with invalid size, expected {} but got {}",
-                        size,
-                        data.len() - 4
-                    );
-                    return Err(Error::InvalidArgument);
-                }
+        if byte & 0x80 == 0 {
+            return Ok((value, index + 1));
+        }
+    }
+
+    warn!("cannot decode varint from {:?}", data);
+    Err(Error::InvalidArgument)
+}
+
+/// Map a signed integer onto an unsigned one via zigzag encoding, so small
+/// negative numbers stay small instead of sign-extending to a huge varint.
+fn zigzag_encode(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+/// Reverse [`zigzag_encode`].
+fn zigzag_decode(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+// ======== the compact-length helpers ========
+/// Encode `value` as a SCALE-style compact integer, used as the length/count
+/// prefix for `RAW`/`STR`/`SEQ`/`DICT` so a short value costs a single byte
+/// and a long one is never capped.
+///
+/// The two least-significant bits of the first byte are a mode tag: `0b00`
+/// single-byte mode, `value` in the upper 6 bits for `0..=63`; `0b01`
+/// two-byte mode, `value = (first_byte >> 2) | (next_byte << 6)` for
+/// `64..=16383`; `0b10` four-byte mode, the 30-bit `value` in the remaining
+/// bits of 4 little-endian bytes for `16384..=2^30-1`; `0b11` big-integer
+/// mode, the upper 6 bits of the first byte hold `(following_bytes - 4)` and
+/// `value` follows as that many little-endian bytes.
+fn encode_compact_len(value: u64) -> Vec<u8> {
+    match value {
+        0..=0x3F => vec![(value as u8) << 2],
+        0x40..=0x3FFF => {
+            let combined = ((value as u16) << 2) | 0b01;
+            combined.to_le_bytes().to_vec()
+        }
+        0x4000..=0x3FFF_FFFF => {
+            let combined = ((value as u32) << 2) | 0b10;
+            combined.to_le_bytes().to_vec()
+        }
+        _ => {
+            let mut len = 4;
+            while len < 8 && value >> (len * 8) != 0 {
+                len += 1;
+            }
 
-                let raw = data[4..size + 4].to_vec();
+            let mut data = vec![(((len - 4) as u8) << 2) | 0b11];
+            data.extend_from_slice(&value.to_le_bytes()[..len]);
+            data
+        }
+    }
+}
 
-                Ok(Value::RAW(raw))
+/// Decode a compact-length prefix written by [`encode_compact_len`],
+/// returning `(value, consumed)`.
+fn decode_compact_len(data: &[u8]) -> Result<(u64, usize)> {
+    let first = *data.first().ok_or(Error::InvalidArgument)?;
+
+    match first & 0b11 {
+        0b00 => Ok(((first >> 2) as u64, 1)),
+        0b01 => {
+            let buf: [u8; 2] = data.get(..2).ok_or(Error::InvalidArgument)?.try_into().unwrap();
+            Ok(((u16::from_le_bytes(buf) >> 2) as u64, 2))
+        }
+        0b10 => {
+            let buf: [u8; 4] = data.get(..4).ok_or(Error::InvalidArgument)?.try_into().unwrap();
+            Ok(((u32::from_le_bytes(buf) >> 2) as u64, 4))
+        }
+        _ => {
+            let len = (first >> 2) as usize + 4;
+            if len > 8 {
+                return Err(Error::InvalidArgument);
             }
-            _ => {
-                warn!("cannot convert value with invalid header {:?}", header);
-                Err(Error::InvalidArgument)
+            let bytes = data.get(1..1 + len).ok_or(Error::InvalidArgument)?;
+
+            let mut buf = [0u8; 8];
+            buf[..len].copy_from_slice(bytes);
+
+            Ok((u64::from_le_bytes(buf), 1 + len))
+        }
+    }
+}
+
+// ======== the binary tags ========
+const TAG_EMPTY: u8 = 0;
+const TAG_DELETED: u8 = 1;
+const TAG_BOOL: u8 = 2;
+const TAG_UINT: u8 = 3;
+const TAG_INT: u8 = 4;
+const TAG_FLOAT: u8 = 5;
+const TAG_RAW: u8 = 6;
+const TAG_STR: u8 = 7;
+const TAG_SEQ: u8 = 8;
+const TAG_DICT: u8 = 9;
+const TAG_BYTES32: u8 = 10;
+
+/// Build the `Error::Decode` for a body that ran out of bytes: `expected`
+/// describes what was being read, at `offset` bytes into the original input.
+fn truncated(offset: usize, expected: &'static str, available: usize) -> Error {
+    Error::Decode {
+        offset,
+        expected,
+        found: format!("only {} byte(s) remaining", available),
+    }
+}
+
+/// Decode a single `Value` starting at `data[0]`, returning `(value, consumed)`
+/// so compound variants can recurse without knowing each other's width ahead
+/// of time. `offset` is `data[0]`'s position in the original input, threaded
+/// through recursive calls so every [`Error::Decode`] reports where in the
+/// whole buffer it went wrong, not just within the current slice.
+fn decode_value_at(data: &[u8], offset: usize) -> Result<(Value, usize)> {
+    let tag = *data
+        .first()
+        .ok_or_else(|| truncated(offset, "a value tag byte", data.len()))?;
+    let rest = &data[1..];
+
+    match tag {
+        TAG_EMPTY => Ok((Value::EMPTY, 1)),
+        TAG_DELETED => Ok((Value::DELETED, 1)),
+        TAG_BOOL => {
+            let byte = *rest
+                .first()
+                .ok_or_else(|| truncated(offset + 1, "1 byte for a BOOL body", rest.len()))?;
+            Ok((Value::BOOL(byte != 0), 2))
+        }
+        TAG_UINT => {
+            let (value, size) = decode_varint(rest)?;
+            Ok((Value::UINT(value), 1 + size))
+        }
+        TAG_INT => {
+            let (value, size) = decode_varint(rest)?;
+            Ok((Value::INT(zigzag_decode(value)), 1 + size))
+        }
+        TAG_FLOAT => {
+            let buf: [u8; 8] = rest
+                .get(..8)
+                .ok_or_else(|| truncated(offset + 1, "8 bytes for a FLOAT body", rest.len()))?
+                .try_into()
+                .unwrap();
+            Ok((Value::FLOAT(f64::from_bits(u64::from_be_bytes(buf))), 1 + 8))
+        }
+        TAG_BYTES32 => {
+            let buf: [u8; 32] = rest
+                .get(..32)
+                .ok_or_else(|| truncated(offset + 1, "32 bytes for a BYTES32 body", rest.len()))?
+                .try_into()
+                .unwrap();
+            Ok((Value::BYTES32(buf), 1 + 32))
+        }
+        TAG_RAW => {
+            let (len, len_size) = decode_compact_len(rest)?;
+            let len = len as usize;
+            let raw = rest
+                .get(len_size..len_size + len)
+                .ok_or_else(|| truncated(offset + 1 + len_size, "a RAW body", rest.len().saturating_sub(len_size)))?;
+
+            Ok((Value::RAW(raw.to_vec()), 1 + len_size + len))
+        }
+        TAG_STR => {
+            let (len, len_size) = decode_compact_len(rest)?;
+            let len = len as usize;
+            let raw = rest
+                .get(len_size..len_size + len)
+                .ok_or_else(|| truncated(offset + 1 + len_size, "a STR body", rest.len().saturating_sub(len_size)))?;
+            let text = String::from_utf8(raw.to_vec()).map_err(|err| Error::Decode {
+                offset: offset + 1 + len_size,
+                expected: "UTF-8 text for a STR body",
+                found: format!("{:?}", err.as_bytes()),
+            })?;
+
+            Ok((Value::STR(text), 1 + len_size + len))
+        }
+        TAG_SEQ => {
+            let (count, len_size) = decode_compact_len(rest)?;
+            let mut consumed = 1 + len_size;
+            let mut items = Vec::with_capacity(count as usize);
+
+            for _ in 0..count {
+                let remaining = data
+                    .get(consumed..)
+                    .ok_or_else(|| truncated(offset + consumed, "a SEQ item", 0))?;
+                let (item, item_size) = decode_value_at(remaining, offset + consumed)?;
+                items.push(item);
+                consumed += item_size;
+            }
+
+            Ok((Value::SEQ(items), consumed))
+        }
+        TAG_DICT => {
+            let (count, len_size) = decode_compact_len(rest)?;
+            let mut consumed = 1 + len_size;
+            let mut map = BTreeMap::new();
+
+            for _ in 0..count {
+                let remaining = data
+                    .get(consumed..)
+                    .ok_or_else(|| truncated(offset + consumed, "a DICT key length", 0))?;
+                let (key_len, key_len_size) = decode_compact_len(remaining)?;
+                consumed += key_len_size;
+
+                let key_len = key_len as usize;
+                let key_bytes = data.get(consumed..consumed + key_len).ok_or_else(|| {
+                    truncated(offset + consumed, "a DICT key", data.len().saturating_sub(consumed))
+                })?;
+                let key = String::from_utf8(key_bytes.to_vec()).map_err(|err| Error::Decode {
+                    offset: offset + consumed,
+                    expected: "UTF-8 text for a DICT key",
+                    found: format!("{:?}", err.as_bytes()),
+                })?;
+                consumed += key_len;
+
+                let remaining = data
+                    .get(consumed..)
+                    .ok_or_else(|| truncated(offset + consumed, "a DICT value", 0))?;
+                let (value, value_size) = decode_value_at(remaining, offset + consumed)?;
+                map.insert(key, value);
+                consumed += value_size;
             }
+
+            Ok((Value::DICT(map), consumed))
+        }
+        _ => {
+            warn!("cannot convert value with invalid tag {}", tag);
+            Err(Error::Decode {
+                offset,
+                expected: "a known value tag (0-10)",
+                found: format!("tag byte {}", tag),
+            })
         }
     }
 }
 
+// ======== the converter ========
+impl Converter for Value {
+    /// the capacity of the type.
+    fn cap(&self) -> usize {
+        self.to_bytes().len()
+    }
+
+    /// Convert the type into binary format: a single tag byte followed by the
+    /// variant's payload. Variable-length payloads (`RAW`/`STR`/`SEQ`/`DICT`)
+    /// are prefixed with a SCALE-style compact-integer length or count (see
+    /// [`encode_compact_len`]), so small values cost a single byte and large
+    /// ones are never capped; `SEQ`/`DICT` nest the full encoding of each
+    /// element so decoding can recurse without a separate length table.
+    ///
+    /// 0       8
+    /// +------+----...----+
+    /// | TAG  |   BODY     |
+    /// +------+----...----+
+    fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            Value::EMPTY => vec![TAG_EMPTY],
+            Value::DELETED => vec![TAG_DELETED],
+            Value::BOOL(value) => vec![TAG_BOOL, *value as u8],
+            Value::UINT(value) => {
+                let mut data = vec![TAG_UINT];
+                data.extend(encode_varint(*value));
+                data
+            }
+            Value::INT(value) => {
+                let mut data = vec![TAG_INT];
+                data.extend(encode_varint(zigzag_encode(*value)));
+                data
+            }
+            Value::FLOAT(value) => {
+                let mut data = vec![TAG_FLOAT];
+                data.extend_from_slice(&value.to_bits().to_be_bytes());
+                data
+            }
+            Value::BYTES32(bytes) => {
+                let mut data = vec![TAG_BYTES32];
+                data.extend_from_slice(bytes);
+                data
+            }
+            Value::RAW(raw) => {
+                let mut data = vec![TAG_RAW];
+                data.extend(encode_compact_len(raw.len() as u64));
+                data.extend_from_slice(raw);
+                data
+            }
+            Value::STR(text) => {
+                let mut data = vec![TAG_STR];
+                let raw = text.as_bytes();
+                data.extend(encode_compact_len(raw.len() as u64));
+                data.extend_from_slice(raw);
+                data
+            }
+            Value::SEQ(items) => {
+                let mut data = vec![TAG_SEQ];
+                data.extend(encode_compact_len(items.len() as u64));
+
+                for item in items {
+                    data.extend(item.to_bytes());
+                }
+
+                data
+            }
+            Value::DICT(map) => {
+                let mut data = vec![TAG_DICT];
+                data.extend(encode_compact_len(map.len() as u64));
+
+                for (key, value) in map {
+                    let raw = key.as_bytes();
+                    data.extend(encode_compact_len(raw.len() as u64));
+                    data.extend_from_slice(raw);
+                    data.extend(value.to_bytes());
+                }
+
+                data
+            }
+        }
+    }
+
+    /// Convert from the binary format written by [`Converter::to_bytes`], which
+    /// starts with the value's tag byte.
+    fn from_bytes(data: &[u8]) -> Result<Self> {
+        decode_value_at(data, 0).map(|(value, _)| value)
+    }
+}
+
 impl Packer for Value {
     /// Convert the type into binary format with type information.
     fn pack(&self) -> Vec<u8> {
@@ -150,10 +517,335 @@ impl Packer for Value {
     where
         Self: Sized,
     {
-        let value = Self::from_bytes(data)?;
-        let size = value.cap();
+        let (value, consumed) = decode_value_at(data, 0)?;
+
+        Ok((value, &data[consumed..]))
+    }
+}
+
+impl CompactPacker for Value {
+    /// Convert the type into the compact binary format.
+    ///
+    /// `RAW` is encoded as a CBOR byte-string header (length, then the raw
+    /// bytes with no padding), `DELETED` has no bytes to carry so it is
+    /// encoded as a standalone bool header instead, and every other variant
+    /// (`EMPTY` included) falls back to [`CBOR_MAJOR_EXT`]: a byte-string header wrapping its
+    /// [`Converter`] encoding, since they have no natural CBOR-style major.
+    fn pack_compact(&self) -> Vec<u8> {
+        match self {
+            Value::DELETED => cbor_encode_header(CBOR_MAJOR_BOOL, 1),
+            Value::RAW(raw) => {
+                let mut data = cbor_encode_header(CBOR_MAJOR_BYTES, raw.len() as u64);
+                data.extend_from_slice(raw);
+                data
+            }
+            _ => {
+                let body = self.to_bytes();
+                let mut data = cbor_encode_header(CBOR_MAJOR_EXT, body.len() as u64);
+                data.extend_from_slice(&body);
+                data
+            }
+        }
+    }
+
+    /// Convert from the compact binary format back into the type, returning the
+    /// unconsumed remainder of `data`.
+    fn unpack_compact(data: &[u8]) -> Result<(Self, &[u8])> {
+        let (major, value, header_len) = cbor_decode_header(data)?;
+        let rest = &data[header_len..];
+
+        match major {
+            CBOR_MAJOR_BOOL => Ok((Value::DELETED, rest)),
+            CBOR_MAJOR_BYTES => {
+                let size = value as usize;
+                if rest.len() < size {
+                    return Err(Error::InvalidArgument);
+                }
+
+                let (raw, rest) = rest.split_at(size);
+
+                Ok((Value::RAW(raw.to_vec()), rest))
+            }
+            CBOR_MAJOR_EXT => {
+                let size = value as usize;
+                if rest.len() < size {
+                    return Err(Error::InvalidArgument);
+                }
+
+                let (body, rest) = rest.split_at(size);
+                let value = Value::from_bytes(body)?;
+
+                Ok((value, rest))
+            }
+            _ => Err(Error::InvalidArgument),
+        }
+    }
+}
+
+// ======== the textual form ========
+impl std::fmt::Display for Value {
+    /// Render the human-readable text syntax read by [`Value`]'s [`FromStr`]
+    /// impl. Not every rendering parses back: `RAW` (`b"<hex>"`), `BYTES32`
+    /// (`x"<hex>"`) and `DELETED` (`<deleted>`) have no `FromStr` form; a
+    /// `FLOAT` with no fractional part renders like an integer and reads back
+    /// as `UINT`/`INT`, and non-finite floats do not parse; `STR` text uses
+    /// Rust's debug escaping, of which `FromStr` decodes only `\n`, `\t`, `\"`
+    /// and `\\`; and `DICT` keys are written unquoted, so only keys made of
+    /// letters, digits and `_` read back.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::EMPTY => write!(f, "null"),
+            Value::DELETED => write!(f, "<deleted>"),
+            Value::BOOL(value) => write!(f, "{}", value),
+            Value::UINT(value) => write!(f, "{}", value),
+            Value::INT(value) => write!(f, "{}", value),
+            Value::FLOAT(value) => write!(f, "{}", value),
+            Value::BYTES32(bytes) => {
+                write!(f, "x\"")?;
+                for byte in bytes {
+                    write!(f, "{:02x}", byte)?;
+                }
+                write!(f, "\"")
+            }
+            Value::RAW(raw) => {
+                write!(f, "b\"")?;
+                for byte in raw {
+                    write!(f, "{:02x}", byte)?;
+                }
+                write!(f, "\"")
+            }
+            Value::STR(text) => write!(f, "{:?}", text),
+            Value::SEQ(items) => {
+                write!(f, "[")?;
+                for (index, item) in items.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+            Value::DICT(map) => {
+                write!(f, "{{")?;
+                for (index, (key, value)) in map.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: {}", key, value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+/// A minimal recursive-descent parser for the text syntax accepted by
+/// [`Value::from_str`], mirroring the `value` rule of `papyrus.pest`.
+struct TextParser<'a> {
+    rest: &'a str,
+}
+
+impl<'a> TextParser<'a> {
+    fn skip_whitespace(&mut self) {
+        self.rest = self.rest.trim_start();
+    }
+
+    fn expect(&mut self, ch: char) -> Result<()> {
+        self.skip_whitespace();
+
+        match self.rest.strip_prefix(ch) {
+            Some(rest) => {
+                self.rest = rest;
+                Ok(())
+            }
+            None => Err(Error::InvalidArgument),
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Value> {
+        self.skip_whitespace();
+
+        match self.rest.chars().next() {
+            Some('[') => self.parse_seq(),
+            Some('{') => self.parse_dict(),
+            Some('"') => self.parse_str(),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.parse_number(),
+            Some(c) if c.is_alphabetic() => self.parse_ident(),
+            _ => Err(Error::InvalidArgument),
+        }
+    }
+
+    fn parse_seq(&mut self) -> Result<Value> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+
+        self.skip_whitespace();
+        if self.rest.starts_with(']') {
+            self.rest = &self.rest[1..];
+            return Ok(Value::SEQ(items));
+        }
+
+        loop {
+            items.push(self.parse_value()?);
+            self.skip_whitespace();
+
+            match self.rest.chars().next() {
+                Some(',') => self.rest = &self.rest[1..],
+                Some(']') => {
+                    self.rest = &self.rest[1..];
+                    break;
+                }
+                _ => return Err(Error::InvalidArgument),
+            }
+        }
 
-        Ok((value, &data[size..]))
+        Ok(Value::SEQ(items))
+    }
+
+    fn parse_dict(&mut self) -> Result<Value> {
+        self.expect('{')?;
+        let mut map = BTreeMap::new();
+
+        self.skip_whitespace();
+        if self.rest.starts_with('}') {
+            self.rest = &self.rest[1..];
+            return Ok(Value::DICT(map));
+        }
+
+        loop {
+            let key = self.parse_key()?;
+            self.expect(':')?;
+            let value = self.parse_value()?;
+            map.insert(key, value);
+
+            self.skip_whitespace();
+            match self.rest.chars().next() {
+                Some(',') => self.rest = &self.rest[1..],
+                Some('}') => {
+                    self.rest = &self.rest[1..];
+                    break;
+                }
+                _ => return Err(Error::InvalidArgument),
+            }
+        }
+
+        Ok(Value::DICT(map))
+    }
+
+    fn parse_key(&mut self) -> Result<String> {
+        self.skip_whitespace();
+
+        if self.rest.starts_with('"') {
+            return match self.parse_str()? {
+                Value::STR(key) => Ok(key),
+                _ => unreachable!("parse_str always returns Value::STR"),
+            };
+        }
+
+        let end = self
+            .rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(self.rest.len());
+
+        if end == 0 {
+            return Err(Error::InvalidArgument);
+        }
+
+        let key = self.rest[..end].to_string();
+        self.rest = &self.rest[end..];
+
+        Ok(key)
+    }
+
+    fn parse_str(&mut self) -> Result<Value> {
+        self.expect('"')?;
+        let mut text = String::new();
+        let mut chars = self.rest.chars();
+
+        loop {
+            match chars.next() {
+                Some('"') => break,
+                Some('\\') => match chars.next() {
+                    Some('n') => text.push('\n'),
+                    Some('t') => text.push('\t'),
+                    Some(c) => text.push(c),
+                    None => return Err(Error::InvalidArgument),
+                },
+                Some(c) => text.push(c),
+                None => return Err(Error::InvalidArgument),
+            }
+        }
+
+        self.rest = chars.as_str();
+
+        Ok(Value::STR(text))
+    }
+
+    fn parse_ident(&mut self) -> Result<Value> {
+        let end = self
+            .rest
+            .find(|c: char| !c.is_alphanumeric())
+            .unwrap_or(self.rest.len());
+        let word = &self.rest[..end];
+
+        let value = match word {
+            "true" => Value::BOOL(true),
+            "false" => Value::BOOL(false),
+            "null" => Value::EMPTY,
+            _ => return Err(Error::InvalidArgument),
+        };
+
+        self.rest = &self.rest[end..];
+
+        Ok(value)
+    }
+
+    fn parse_number(&mut self) -> Result<Value> {
+        let end = self
+            .rest
+            .find(|c: char| !(c.is_ascii_digit() || "-.eE+".contains(c)))
+            .unwrap_or(self.rest.len());
+        let word = &self.rest[..end];
+        self.rest = &self.rest[end..];
+
+        if word.contains('.') || word.contains('e') || word.contains('E') {
+            return word
+                .parse::<f64>()
+                .map(Value::FLOAT)
+                .map_err(|_| Error::InvalidArgument);
+        }
+
+        if word.starts_with('-') {
+            return word
+                .parse::<i64>()
+                .map(Value::INT)
+                .map_err(|_| Error::InvalidArgument);
+        }
+
+        word.parse::<u64>()
+            .map(Value::UINT)
+            .map_err(|_| Error::InvalidArgument)
+    }
+}
+
+impl FromStr for Value {
+    type Err = Error;
+
+    /// Parse the text syntax produced by [`Value`]'s [`std::fmt::Display`] impl:
+    /// `null`, `true`/`false`, bare integers (`UINT`) or `-`-prefixed ones
+    /// (`INT`), floats, `"..."` strings, `[a, b, ...]` sequences, and
+    /// `{key: value, ...}` dictionaries with bareword or quoted keys.
+    fn from_str(text: &str) -> Result<Self> {
+        let mut parser = TextParser { rest: text };
+        let value = parser.parse_value()?;
+        parser.skip_whitespace();
+
+        if !parser.rest.is_empty() {
+            warn!("trailing data after value: {:?}", parser.rest);
+            return Err(Error::InvalidArgument);
+        }
+
+        Ok(value)
     }
 }
 
@@ -162,6 +854,121 @@ mod tests {
     use super::*;
     use paste::paste;
 
+    macro_rules! test_compact_len_round_trip {
+        ($name:ident, $value:expr) => {
+            paste! {
+                #[test]
+                fn [<test_compact_len_round_trip_ $name>]() {
+                    let value: u64 = $value;
+                    let data = encode_compact_len(value);
+
+                    assert_eq!(decode_compact_len(&data), Ok((value, data.len())));
+                }
+            }
+        };
+    }
+
+    test_compact_len_round_trip!(zero, 0);
+    test_compact_len_round_trip!(single_byte_max, 0x3F);
+    test_compact_len_round_trip!(two_byte_min, 0x40);
+    test_compact_len_round_trip!(two_byte_max, 0x3FFF);
+    test_compact_len_round_trip!(four_byte_min, 0x4000);
+    test_compact_len_round_trip!(four_byte_max, 0x3FFF_FFFF);
+    test_compact_len_round_trip!(big_int_min, 0x4000_0000);
+    test_compact_len_round_trip!(big_int_u32_max, u32::MAX as u64);
+    test_compact_len_round_trip!(big_int_u64_max, u64::MAX);
+
+    #[test]
+    fn test_compact_len_single_byte_mode_costs_one_byte() {
+        assert_eq!(encode_compact_len(0).len(), 1);
+        assert_eq!(encode_compact_len(0x3F).len(), 1);
+    }
+
+    #[test]
+    fn test_value_convert_is_tag_prefixed() {
+        // hard-coded tag+compact-length layout, independent of the host endianness
+        let data: Vec<u8> = vec![TAG_RAW, 3 << 2, b'a', b'b', b'c'];
+        assert_eq!(Value::from_bytes(&data), Ok(Value::RAW(b"abc".to_vec())));
+    }
+
+    #[test]
+    fn test_bytes32_skips_the_length_field() {
+        let value: Value = [1u8; 32].into();
+
+        assert_eq!(value.to_bytes().len(), 1 + 32);
+    }
+
+    #[test]
+    fn test_as_i64_on_int_and_other_variants() {
+        assert_eq!(Value::INT(-7).as_i64(), Some(-7));
+        assert_eq!(Value::UINT(7).as_i64(), None);
+        assert_eq!(Value::EMPTY.as_i64(), None);
+    }
+
+    #[test]
+    fn test_as_bool_on_bool_and_other_variants() {
+        assert_eq!(Value::BOOL(true).as_bool(), Some(true));
+        assert_eq!(Value::UINT(1).as_bool(), None);
+        assert_eq!(Value::EMPTY.as_bool(), None);
+    }
+
+    #[test]
+    fn test_decode_error_on_truncated_buffer_reports_offset_and_expected() {
+        let data: Vec<u8> = vec![TAG_BOOL];
+
+        assert_eq!(
+            Value::from_bytes(&data),
+            Err(Error::Decode {
+                offset: 1,
+                expected: "1 byte for a BOOL body",
+                found: "only 0 byte(s) remaining".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_error_on_unknown_tag_reports_the_bad_tag() {
+        let data: Vec<u8> = vec![0xFF];
+
+        assert_eq!(
+            Value::from_bytes(&data),
+            Err(Error::Decode {
+                offset: 0,
+                expected: "a known value tag (0-10)",
+                found: "tag byte 255".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_error_offset_inside_a_seq_points_at_the_bad_item() {
+        let mut data = encode_compact_len(1);
+        data.insert(0, TAG_SEQ);
+        data.push(0xFF);
+
+        assert_eq!(
+            Value::from_bytes(&data),
+            Err(Error::Decode {
+                offset: data.len() - 1,
+                expected: "a known value tag (0-10)",
+                found: "tag byte 255".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_unpack_iter_surfaces_corruption_instead_of_stopping_silently() {
+        let value: Value = "a".into();
+        let mut data = value.pack();
+        data.push(0xFF);
+
+        let items: Vec<Result<Value>> = Value::unpack_iter(&data).collect();
+
+        assert_eq!(items.len(), 2);
+        assert!(items[0].is_ok());
+        assert!(items[1].is_err());
+    }
+
     #[test]
     fn test_empty_value() {
         let value: Value = Value::EMPTY;
@@ -250,6 +1057,150 @@ mod tests {
     test_value!(single_char, "a");
     test_value!(multi_char, "aaaaaaaa");
 
+    macro_rules! test_value_round_trip {
+        ($name:ident, $value:expr) => {
+            paste! {
+                #[test]
+                fn [<test_value_round_trip_ $name>]() {
+                    let value: Value = $value;
+                    let data: Vec<u8> = value.to_bytes();
+
+                    assert_eq!(data.len(), value.cap());
+                    assert_eq!(Value::from_bytes(&data), Ok(value.clone()));
+
+                    let rest: &[u8] = &vec![];
+                    assert_eq!(Value::unpack(&value.pack()), Ok((value, rest)));
+                }
+            }
+        };
+    }
+
+    test_value_round_trip!(bool_true, Value::BOOL(true));
+    test_value_round_trip!(bool_false, Value::BOOL(false));
+    test_value_round_trip!(uint, Value::UINT(42));
+    test_value_round_trip!(uint_large, Value::UINT(u64::MAX));
+    test_value_round_trip!(int_positive, Value::INT(42));
+    test_value_round_trip!(int_negative, Value::INT(-42));
+    test_value_round_trip!(int_min, Value::INT(i64::MIN));
+    test_value_round_trip!(int_max, Value::INT(i64::MAX));
+    test_value_round_trip!(float, Value::FLOAT(3.5));
+    test_value_round_trip!(float_negative, Value::FLOAT(-1.25));
+    test_value_round_trip!(bytes32, Value::BYTES32([7u8; 32]));
+    test_value_round_trip!(str, Value::STR("hello".to_string()));
+    test_value_round_trip!(seq_empty, Value::SEQ(vec![]));
+    test_value_round_trip!(
+        seq,
+        Value::SEQ(vec![Value::UINT(1), Value::STR("two".to_string()), Value::BOOL(true)])
+    );
+    test_value_round_trip!(dict_empty, Value::DICT(BTreeMap::new()));
+    test_value_round_trip!(dict, {
+        let mut map = BTreeMap::new();
+        map.insert("a".to_string(), Value::BOOL(true));
+        map.insert("b".to_string(), Value::UINT(7));
+        Value::DICT(map)
+    });
+    test_value_round_trip!(nested, {
+        let mut inner = BTreeMap::new();
+        inner.insert("x".to_string(), Value::SEQ(vec![Value::INT(-1), Value::FLOAT(2.5)]));
+
+        Value::SEQ(vec![
+            Value::DICT(inner),
+            Value::STR("tail".to_string()),
+            Value::SEQ(vec![]),
+        ])
+    });
+
+    macro_rules! test_raw_size_round_trip {
+        ($name:ident, $size:expr) => {
+            paste! {
+                #[test]
+                fn [<test_raw_size_round_trip_ $name>]() {
+                    let value: Value = Value::RAW(vec![b'x'; $size]);
+                    let data: Vec<u8> = value.to_bytes();
+
+                    assert_eq!(data.len(), value.cap());
+                    assert_eq!(Value::from_bytes(&data), Ok(value.clone()));
+
+                    let rest: &[u8] = &vec![];
+                    assert_eq!(Value::unpack(&value.pack()), Ok((value, rest)));
+                }
+            }
+        };
+    }
+
+    test_raw_size_round_trip!(empty, 0);
+    test_raw_size_round_trip!(single_byte_mode_max, 0x3F);
+    test_raw_size_round_trip!(two_byte_mode_min, 0x40);
+    test_raw_size_round_trip!(two_byte_mode_max, 0x3FFF);
+    test_raw_size_round_trip!(four_byte_mode_min, 0x4000);
+    // a value past the old 0x00FFFFFF (~16 MiB) cap that a fixed 24-bit
+    // length header could never address.
+    test_raw_size_round_trip!(past_old_24_bit_cap, 17 * 1024 * 1024);
+
+    #[test]
+    fn test_deleted_value_compact_packer() {
+        let mut value: Value = Value::EMPTY;
+        value.delete();
+
+        let data: Vec<u8> = value.pack_compact();
+        let rest: &[u8] = &vec![];
+
+        assert_eq!(Value::unpack_compact(&data), Ok((Value::DELETED, rest)));
+    }
+
+    macro_rules! test_value_compact_packer {
+        ($name:ident, $data:expr) => {
+            paste! {
+                #[test]
+                fn [<test_value_ $name _compact_packer>]() {
+                    let value: Value = $data.into();
+                    let data: Vec<u8> = value.pack_compact();
+                    let rest: &[u8] = &vec![];
+
+                    assert_eq!(
+                        Value::unpack_compact(&data),
+                        Ok((Value::RAW($data.as_bytes().to_vec()), rest))
+                    );
+                }
+            }
+        };
+    }
+
+    test_value_compact_packer!(empty, "");
+    test_value_compact_packer!(single_char, "a");
+    test_value_compact_packer!(multi_char, "aaaaaaaa");
+
+    #[test]
+    fn test_value_compact_packer_is_smaller_than_fixed_width() {
+        let value: Value = "a".into();
+
+        assert!(value.pack_compact().len() < value.pack().len());
+    }
+
+    macro_rules! test_value_compact_round_trip {
+        ($name:ident, $value:expr) => {
+            paste! {
+                #[test]
+                fn [<test_value_compact_round_trip_ $name>]() {
+                    let value: Value = $value;
+                    let data: Vec<u8> = value.pack_compact();
+                    let rest: &[u8] = &vec![];
+
+                    assert_eq!(Value::unpack_compact(&data), Ok((value, rest)));
+                }
+            }
+        };
+    }
+
+    test_value_compact_round_trip!(bool_true, Value::BOOL(true));
+    test_value_compact_round_trip!(uint, Value::UINT(9001));
+    test_value_compact_round_trip!(int_negative, Value::INT(-9001));
+    test_value_compact_round_trip!(float, Value::FLOAT(1.5));
+    test_value_compact_round_trip!(
+        seq,
+        Value::SEQ(vec![Value::UINT(1), Value::STR("two".to_string())])
+    );
+
     macro_rules! test_value_unpack_iter {
         ($count:expr) => {
             paste! {
@@ -277,4 +1228,57 @@ mod tests {
     test_value_unpack_iter!(64);
     test_value_unpack_iter!(4096);
     test_value_unpack_iter!(65535);
+
+    macro_rules! test_value_text_round_trip {
+        ($name:ident, $value:expr) => {
+            paste! {
+                #[test]
+                fn [<test_value_text_round_trip_ $name>]() {
+                    let value: Value = $value;
+                    let text = value.to_string();
+
+                    assert_eq!(text.parse::<Value>(), Ok(value));
+                }
+            }
+        };
+    }
+
+    test_value_text_round_trip!(empty, Value::EMPTY);
+    test_value_text_round_trip!(bool_true, Value::BOOL(true));
+    test_value_text_round_trip!(bool_false, Value::BOOL(false));
+    test_value_text_round_trip!(uint, Value::UINT(1));
+    test_value_text_round_trip!(int_negative, Value::INT(-7));
+    test_value_text_round_trip!(float, Value::FLOAT(1.5));
+    test_value_text_round_trip!(str, Value::STR("two".to_string()));
+    test_value_text_round_trip!(str_with_escape, Value::STR("a\nb".to_string()));
+    test_value_text_round_trip!(seq_empty, Value::SEQ(vec![]));
+    test_value_text_round_trip!(
+        seq,
+        Value::SEQ(vec![Value::UINT(1), Value::STR("two".to_string()), Value::BOOL(true)])
+    );
+    test_value_text_round_trip!(dict_empty, Value::DICT(BTreeMap::new()));
+    test_value_text_round_trip!(dict, {
+        let mut map = BTreeMap::new();
+        map.insert("a".to_string(), Value::BOOL(true));
+        map.insert("b".to_string(), Value::UINT(7));
+        Value::DICT(map)
+    });
+
+    #[test]
+    fn test_value_from_str_parses_repl_example() {
+        let value: Value = "[1, \"two\", {a: true}]".parse().unwrap();
+
+        let mut dict = BTreeMap::new();
+        dict.insert("a".to_string(), Value::BOOL(true));
+
+        assert_eq!(
+            value,
+            Value::SEQ(vec![Value::UINT(1), Value::STR("two".to_string()), Value::DICT(dict)])
+        );
+    }
+
+    #[test]
+    fn test_value_from_str_rejects_trailing_garbage() {
+        assert_eq!("1 2".parse::<Value>(), Err(Error::InvalidArgument));
+    }
 }